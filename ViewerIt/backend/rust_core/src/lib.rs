@@ -19,6 +19,23 @@ pub struct IntersectionResult {
     pub total_unique_keys: usize,
 }
 
+/// Pairwise dataset-relationship matrices across the tracked files.
+///
+/// Rows and columns follow `filenames`, which is sorted for deterministic
+/// output so callers can line the matrices up with `get_filenames()`.
+#[pyclass]
+#[derive(Clone)]
+pub struct RelationshipMatrices {
+    #[pyo3(get)]
+    pub filenames: Vec<String>,
+    /// Symmetric Jaccard similarity (`|A∩B| / |A∪B|`) per file pair.
+    #[pyo3(get)]
+    pub jaccard: Vec<Vec<f64>>,
+    /// Asymmetric containment (`|A∩B| / |A|`); row = A, column = B.
+    #[pyo3(get)]
+    pub containment: Vec<Vec<f64>>,
+}
+
 /// A fast engine for calculating intersections across multiple datasets.
 #[pyclass]
 pub struct FastIntersector {
@@ -52,7 +69,9 @@ impl FastIntersector {
         // Sorting keys ensures deterministic output for the matrix
         sorted_keys.par_sort();
 
-        let filenames: Vec<String> = self.file_map.keys().cloned().collect();
+        let mut filenames: Vec<String> = self.file_map.keys().cloned().collect();
+        // Sorted so the presence matrix columns line up with `get_filenames()`.
+        filenames.sort();
         let total_unique_keys = sorted_keys.len();
         
         // 2. Build Presence Matrix (Parallelized)
@@ -99,9 +118,67 @@ impl FastIntersector {
         }
     }
     
-    /// Get the list of files currently tracked
+    /// Get the list of files currently tracked, in sorted order so callers can
+    /// cross-index it against `compute()` columns and `relationship_matrices()`.
     pub fn get_filenames(&self) -> Vec<String> {
-        self.file_map.keys().cloned().collect()
+        let mut filenames: Vec<String> = self.file_map.keys().cloned().collect();
+        filenames.sort();
+        filenames
+    }
+
+    /// Build pairwise Jaccard-similarity and containment matrices.
+    ///
+    /// This turns the intersector from a pure membership tool into a
+    /// dataset-relationship analyzer: callers can rank which datasets are
+    /// near-duplicates (high Jaccard), which are strict subsets (containment
+    /// near 1.0 in one direction), and which are disjoint. File ordering is
+    /// sorted and shared by both matrices, and the computation is parallelized
+    /// across rows with rayon.
+    pub fn relationship_matrices(&self) -> RelationshipMatrices {
+        // Deterministic file ordering for stable matrix indices.
+        let mut filenames: Vec<String> = self.file_map.keys().cloned().collect();
+        filenames.sort();
+
+        let sets: Vec<&HashSet<String, RandomState>> = filenames
+            .iter()
+            .map(|name| self.file_map.get(name).unwrap())
+            .collect();
+
+        let rows: Vec<(Vec<f64>, Vec<f64>)> = (0..sets.len())
+            .into_par_iter()
+            .map(|i| {
+                let a = sets[i];
+                let mut jaccard_row = vec![0.0; sets.len()];
+                let mut containment_row = vec![0.0; sets.len()];
+
+                for (j, b) in sets.iter().enumerate() {
+                    let intersection = intersection_count(a, b);
+                    let union = a.len() + b.len() - intersection;
+
+                    jaccard_row[j] = if union == 0 {
+                        1.0
+                    } else {
+                        intersection as f64 / union as f64
+                    };
+                    containment_row[j] = if a.is_empty() {
+                        1.0
+                    } else {
+                        intersection as f64 / a.len() as f64
+                    };
+                }
+
+                (jaccard_row, containment_row)
+            })
+            .collect();
+
+        let jaccard = rows.iter().map(|(j, _)| j.clone()).collect();
+        let containment = rows.into_iter().map(|(_, c)| c).collect();
+
+        RelationshipMatrices {
+            filenames,
+            jaccard,
+            containment,
+        }
     }
     
     /// Clear all files from the intersector
@@ -115,10 +192,20 @@ impl FastIntersector {
     }
 }
 
+/// Count shared keys between two sets, iterating over the smaller one.
+fn intersection_count(
+    a: &HashSet<String, RandomState>,
+    b: &HashSet<String, RandomState>,
+) -> usize {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small.iter().filter(|k| large.contains(*k)).count()
+}
+
 /// A Python module implemented in Rust.
 #[pymodule]
 fn viewerit_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<FastIntersector>()?;
     m.add_class::<IntersectionResult>()?;
+    m.add_class::<RelationshipMatrices>()?;
     Ok(())
 }
@@ -0,0 +1,195 @@
+//! Metric-tree index for near-duplicate search over fingerprints
+//!
+//! The fingerprint module produces 64-bit simhashes whose only comparison
+//! primitive is `hamming_distance`, which forces callers into an O(n²)
+//! pairwise scan. A BK-tree turns that raw output into a similarity-search
+//! subsystem: it works over any discrete metric, so a single query visits
+//! only the branches the triangle inequality cannot rule out.
+
+use crate::fingerprint::hamming_distance;
+use crate::types::FileEntry;
+use std::collections::HashMap;
+
+/// A node in the arena-backed BK-tree.
+struct BkNode {
+    /// The simhash stored at this node.
+    hash: u64,
+    /// Index of the owning `FileEntry` in the slice the tree was built from.
+    entry_idx: usize,
+    /// Child edges keyed by their integer Hamming distance to this node.
+    children: HashMap<u32, usize>,
+}
+
+/// BK-tree over simhash fingerprints keyed on Hamming distance.
+///
+/// Nodes are held in an arena so the tree can be built and queried without
+/// reference-counted pointers; `root` indexes the first inserted node.
+#[derive(Default)]
+pub struct SimhashIndex {
+    nodes: Vec<BkNode>,
+    root: Option<usize>,
+}
+
+impl SimhashIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        SimhashIndex {
+            nodes: Vec::new(),
+            root: None,
+        }
+    }
+
+    /// Insert a simhash paired with the index of its `FileEntry`.
+    ///
+    /// Walks from the root following the child edge labeled with the Hamming
+    /// distance to the current node, attaching the new node at the first
+    /// missing edge.
+    pub fn insert(&mut self, hash: u64, entry_idx: usize) {
+        let new_idx = self.nodes.len();
+        self.nodes.push(BkNode {
+            hash,
+            entry_idx,
+            children: HashMap::new(),
+        });
+
+        let mut current = match self.root {
+            Some(root) => root,
+            None => {
+                self.root = Some(new_idx);
+                return;
+            }
+        };
+
+        loop {
+            let d = hamming_distance(self.nodes[current].hash, hash);
+            match self.nodes[current].children.get(&d).copied() {
+                Some(child) => current = child,
+                None => {
+                    self.nodes[current].children.insert(d, new_idx);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Find every indexed entry whose simhash lies within `max_distance` bits
+    /// of `hash`, returned as `(entry_idx, distance)` pairs.
+    ///
+    /// At each visited node the distance `d` is computed; the node is emitted
+    /// when `d <= max_distance`, and the walk recurses only into children whose
+    /// edge label falls in `[d - max_distance, d + max_distance]`.
+    pub fn find_similar(&self, hash: u64, max_distance: u32) -> Vec<(usize, u32)> {
+        let mut matches = Vec::new();
+        let root = match self.root {
+            Some(root) => root,
+            None => return matches,
+        };
+
+        let mut stack = vec![root];
+        while let Some(idx) = stack.pop() {
+            let node = &self.nodes[idx];
+            let d = hamming_distance(node.hash, hash);
+            if d <= max_distance {
+                matches.push((node.entry_idx, d));
+            }
+
+            let low = d.saturating_sub(max_distance);
+            let high = d + max_distance;
+            for (&edge, &child) in &node.children {
+                if edge >= low && edge <= high {
+                    stack.push(child);
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Number of fingerprints held in the index.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the index holds no fingerprints.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// Build a simhash index over the entries that carry a simhash.
+///
+/// Entries without a simhash (binary/unknown files) are skipped; the returned
+/// index stores each entry's position in `entries` so lookups map back to the
+/// original slice.
+pub fn build_simhash_index(entries: &[FileEntry]) -> SimhashIndex {
+    let mut index = SimhashIndex::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if let Some(hash) = entry.simhash {
+            index.insert(hash, i);
+        }
+    }
+    index
+}
+
+/// Group entries into near-duplicate clusters within `max_distance` bits.
+///
+/// Uses a greedy sweep: each still-unassigned entry seeds a cluster that
+/// absorbs every still-unassigned neighbour the index reports within the
+/// threshold of that seed; neighbours of those members are not followed
+/// transitively. Entries without a simhash never join a cluster.
+pub fn cluster(entries: &[FileEntry], max_distance: u32) -> Vec<Vec<usize>> {
+    let index = build_simhash_index(entries);
+    let mut assigned = vec![false; entries.len()];
+    let mut clusters = Vec::new();
+
+    for (i, entry) in entries.iter().enumerate() {
+        if assigned[i] {
+            continue;
+        }
+        let hash = match entry.simhash {
+            Some(hash) => hash,
+            None => continue,
+        };
+
+        let mut members = Vec::new();
+        for (neighbour, _) in index.find_similar(hash, max_distance) {
+            if !assigned[neighbour] {
+                assigned[neighbour] = true;
+                members.push(neighbour);
+            }
+        }
+        if !members.is_empty() {
+            members.sort_unstable();
+            clusters.push(members);
+        }
+    }
+
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_similar_bands() {
+        let mut index = SimhashIndex::new();
+        index.insert(0b0000, 0);
+        index.insert(0b0001, 1);
+        index.insert(0b1111, 2);
+
+        let mut within_one = index.find_similar(0b0000, 1);
+        within_one.sort_unstable();
+        assert_eq!(within_one, vec![(0, 0), (1, 1)]);
+
+        let all = index.find_similar(0b0000, 64);
+        assert_eq!(all.len(), 3);
+    }
+
+    #[test]
+    fn test_empty_index() {
+        let index = SimhashIndex::new();
+        assert!(index.is_empty());
+        assert!(index.find_similar(0, 8).is_empty());
+    }
+}
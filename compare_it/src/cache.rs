@@ -0,0 +1,148 @@
+//! Persistent fingerprint cache
+//!
+//! Each run of `compute_fingerprints` otherwise re-reads and re-hashes every
+//! file even when nothing changed. This module serializes the computed
+//! fingerprints keyed by `(canonical path, size, modified-time)` and reloads
+//! them on the next scan, so unchanged files in a large mostly-static tree skip
+//! the file read entirely.
+//!
+//! The cache carries a `signature` derived from the normalization options and
+//! the selected hash algorithm; if either changes, the stored fingerprints are
+//! no longer valid and the whole cache is discarded on load.
+
+use crate::fingerprint::hash_string;
+use crate::types::{FileEntry, HashAlgorithm, NormalizationOptions};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Fingerprints stored for a single file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFingerprint {
+    pub size: u64,
+    pub modified_time: Option<u64>,
+    pub content_hash: String,
+    pub hash_algorithm: HashAlgorithm,
+    pub partial_hash: Option<String>,
+    pub simhash: Option<u64>,
+    pub schema_signature: Option<String>,
+}
+
+/// On-disk fingerprint cache keyed by canonical path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FingerprintCache {
+    /// Invalidation signature (normalization options + hash algorithm).
+    signature: u64,
+    /// Fingerprints keyed by canonical path string.
+    entries: HashMap<String, CachedFingerprint>,
+}
+
+/// Derive the invalidation signature from fingerprint-affecting settings.
+pub fn cache_signature(normalization: &NormalizationOptions, algorithm: HashAlgorithm) -> u64 {
+    // Build a stable string describing every setting that alters fingerprints.
+    let desc = format!(
+        "{:?}|{:?}",
+        (
+            normalization.ignore_eol,
+            normalization.ignore_trailing_ws,
+            normalization.ignore_all_ws,
+            normalization.ignore_case,
+            normalization.skip_empty_lines,
+        ),
+        algorithm,
+    );
+    hash_string(&desc)
+}
+
+impl FingerprintCache {
+    /// Load a cache from disk, discarding it if the signature no longer matches.
+    ///
+    /// A missing or unparseable cache file yields an empty cache rather than an
+    /// error, so a corrupted cache degrades to a full rescan.
+    pub fn load(path: &Path, signature: u64) -> Self {
+        let loaded = fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<FingerprintCache>(&bytes).ok());
+
+        match loaded {
+            Some(cache) if cache.signature == signature => cache,
+            _ => FingerprintCache {
+                signature,
+                entries: HashMap::new(),
+            },
+        }
+    }
+
+    /// Persist the cache to disk.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec(self)?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write fingerprint cache to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Canonicalize a path for use as a cache key, falling back to the raw path.
+    fn key(path: &Path) -> String {
+        fs::canonicalize(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Look up fingerprints for a file, returning them only when size and
+    /// modified-time still match the on-disk file.
+    pub fn lookup(&self, entry: &FileEntry) -> Option<&CachedFingerprint> {
+        let cached = self.entries.get(&Self::key(&entry.path))?;
+        if cached.size == entry.size && cached.modified_time == entry.modified_time {
+            Some(cached)
+        } else {
+            None
+        }
+    }
+
+    /// Record the fingerprints currently held on `entry`.
+    pub fn store(&mut self, entry: &FileEntry) {
+        self.entries.insert(
+            Self::key(&entry.path),
+            CachedFingerprint {
+                size: entry.size,
+                modified_time: entry.modified_time,
+                content_hash: entry.content_hash.clone(),
+                hash_algorithm: entry.hash_algorithm,
+                partial_hash: entry.partial_hash.clone(),
+                simhash: entry.simhash,
+                schema_signature: entry.schema_signature.clone(),
+            },
+        );
+    }
+
+    /// Reload a freshly indexed slice from the cache in one pass.
+    ///
+    /// Every entry whose `(path, size, modified-time)` still matches has its
+    /// stored fingerprints copied in place and is skipped by the caller; the
+    /// returned vector holds the positions that changed (or were never cached)
+    /// and therefore still need hashing/simhash/schema extraction. This is the
+    /// index-level view of [`lookup`](Self::lookup)/[`apply`](Self::apply).
+    pub fn hydrate_index(&self, entries: &mut [FileEntry]) -> Vec<usize> {
+        let mut stale = Vec::new();
+        for (i, entry) in entries.iter_mut().enumerate() {
+            match self.lookup(entry).cloned() {
+                Some(cached) => self.apply(entry, &cached),
+                None => stale.push(i),
+            }
+        }
+        stale
+    }
+
+    /// Copy cached fingerprints onto a freshly indexed entry.
+    pub fn apply(&self, entry: &mut FileEntry, cached: &CachedFingerprint) {
+        let _ = self; // keyed lookups already happened in `lookup`
+        entry.content_hash = cached.content_hash.clone();
+        entry.hash_algorithm = cached.hash_algorithm;
+        entry.partial_hash = cached.partial_hash.clone();
+        entry.simhash = cached.simhash;
+        entry.schema_signature = cached.schema_signature.clone();
+    }
+}
@@ -0,0 +1,84 @@
+//! Character-level diffing for mismatched structured cells
+//!
+//! Raw `value1`/`value2` pairs say a cell changed but not *what* changed. This
+//! module computes the shortest edit script between the two strings and runs a
+//! semantic-cleanup pass that folds tiny equal runs trapped between edits into
+//! the surrounding edits, so `2024-01-01` vs `2025-02-01` marks the whole
+//! `4-01`→`5-02` span as changed instead of two single-character edits split
+//! by an unchanged `-0`.
+
+use crate::types::{DiffChunk, DiffOp};
+use similar::{ChangeTag, TextDiff};
+
+/// Equal runs this short, when flanked by edits on both sides, are absorbed
+/// into the surrounding edit for a more readable highlight.
+const SEMANTIC_CLEANUP_THRESHOLD: usize = 4;
+
+/// Compute character-level diff chunks between two cell values.
+pub fn diff_cell(value1: &str, value2: &str) -> Vec<DiffChunk> {
+    let diff = TextDiff::from_chars(value1, value2);
+
+    // Collapse consecutive same-tag changes into runs.
+    let mut chunks: Vec<DiffChunk> = Vec::new();
+    for change in diff.iter_all_changes() {
+        let op = match change.tag() {
+            ChangeTag::Equal => DiffOp::Equal,
+            ChangeTag::Delete => DiffOp::Delete,
+            ChangeTag::Insert => DiffOp::Insert,
+        };
+        match chunks.last_mut() {
+            Some(last) if last.op == op => last.text.push_str(change.value()),
+            _ => chunks.push(DiffChunk {
+                op,
+                text: change.value().to_string(),
+            }),
+        }
+    }
+
+    semantic_cleanup(chunks)
+}
+
+/// Merge short equal runs surrounded by edits into the edits around them.
+fn semantic_cleanup(chunks: Vec<DiffChunk>) -> Vec<DiffChunk> {
+    if chunks.len() < 3 {
+        return chunks;
+    }
+
+    let mut out: Vec<DiffChunk> = Vec::with_capacity(chunks.len());
+    let mut i = 0;
+    while i < chunks.len() {
+        let chunk = &chunks[i];
+        let surrounded = i > 0
+            && i + 1 < chunks.len()
+            && chunk.op == DiffOp::Equal
+            && chunks[i - 1].op != DiffOp::Equal
+            && chunks[i + 1].op != DiffOp::Equal
+            && chunk.text.chars().count() <= SEMANTIC_CLEANUP_THRESHOLD;
+
+        if surrounded {
+            // Rewrite this equal run as both a delete and an insert so the
+            // flanking edits read as one continuous change.
+            out.push(DiffChunk {
+                op: DiffOp::Delete,
+                text: chunk.text.clone(),
+            });
+            out.push(DiffChunk {
+                op: DiffOp::Insert,
+                text: chunk.text.clone(),
+            });
+        } else {
+            out.push(chunk.clone());
+        }
+        i += 1;
+    }
+
+    // Coalesce any adjacent same-op runs produced by the rewrite.
+    let mut merged: Vec<DiffChunk> = Vec::with_capacity(out.len());
+    for chunk in out {
+        match merged.last_mut() {
+            Some(last) if last.op == chunk.op => last.text.push_str(&chunk.text),
+            _ => merged.push(chunk),
+        }
+    }
+    merged
+}
@@ -0,0 +1,53 @@
+//! Perceptual image comparison
+//!
+//! Images are scored by the Hamming distance between the 64-bit perceptual
+//! hashes computed during indexing (stored in [`FileEntry::simhash`]). The raw
+//! distance is mapped onto a 0.0–1.0 similarity and a banded verdict so image
+//! folders can be diffed alongside the text/CSV/binary modes.
+
+use crate::fingerprint::hamming_distance;
+use crate::types::{FileEntry, ImageComparisonResult};
+
+/// Distance at or below which two images are treated as the same.
+const NEAR_IDENTICAL_DISTANCE: u32 = 5;
+/// Distance at or below which two images are considered visually similar.
+const SIMILAR_DISTANCE: u32 = 14;
+
+/// Compare two images by perceptual hash.
+///
+/// Images without a perceptual hash (decode failures during indexing) score as
+/// fully different rather than erroring, so a bad file never aborts a run.
+pub fn compare_image_files(file1: &FileEntry, file2: &FileEntry) -> ImageComparisonResult {
+    let linked_id = format!(
+        "{}:{}",
+        &file1.content_hash[..16.min(file1.content_hash.len())],
+        &file2.content_hash[..16.min(file2.content_hash.len())]
+    );
+
+    let (hamming_distance, similarity_score, verdict, identical) =
+        match (file1.simhash, file2.simhash) {
+            (Some(h1), Some(h2)) => {
+                let d = hamming_distance(h1, h2);
+                let score = 1.0 - d as f64 / 64.0;
+                let (verdict, identical) = if d <= NEAR_IDENTICAL_DISTANCE {
+                    ("near-identical", true)
+                } else if d <= SIMILAR_DISTANCE {
+                    ("similar", false)
+                } else {
+                    ("different", false)
+                };
+                (d, score, verdict.to_string(), identical)
+            }
+            _ => (64, 0.0, "different".to_string(), false),
+        };
+
+    ImageComparisonResult {
+        linked_id,
+        file1_path: file1.path.display().to_string(),
+        file2_path: file2.path.display().to_string(),
+        hamming_distance,
+        verdict,
+        similarity_score,
+        identical,
+    }
+}
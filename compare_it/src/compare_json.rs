@@ -0,0 +1,255 @@
+//! Semantic JSON comparison
+//!
+//! Diffing `.json` files as text flags meaningless reorderings and formatting
+//! changes as differences. This module parses both files into
+//! `serde_json::Value`, walks them structurally, and reports typed differences
+//! keyed by JSON-pointer-style paths (e.g. `/items/3/name`). Object keys are
+//! compared as sets; arrays are compared positionally by default, or
+//! order-insensitively (matching elements by best similarity) when requested.
+
+use crate::types::{CompareConfig, FileEntry, JsonComparisonResult, JsonDifference, JsonDiffKind};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+
+/// Compare two JSON files and produce a structural result.
+pub fn compare_json_files(
+    file1: &FileEntry,
+    file2: &FileEntry,
+    config: &CompareConfig,
+) -> Result<JsonComparisonResult> {
+    let text1 = fs::read_to_string(&file1.path)
+        .with_context(|| format!("Failed to read {}", file1.path.display()))?;
+    let text2 = fs::read_to_string(&file2.path)
+        .with_context(|| format!("Failed to read {}", file2.path.display()))?;
+
+    let value1: Value =
+        serde_json::from_str(&text1).with_context(|| format!("Invalid JSON in {}", file1.path.display()))?;
+    let value2: Value =
+        serde_json::from_str(&text2).with_context(|| format!("Invalid JSON in {}", file2.path.display()))?;
+
+    let mut walker = Walker {
+        unordered_arrays: config.json_array_order_insensitive,
+        differences: Vec::new(),
+        total_leaves: 0,
+        matching_leaves: 0,
+    };
+    walker.walk(&value1, &value2, "");
+
+    let similarity_score = if walker.total_leaves == 0 {
+        1.0
+    } else {
+        walker.matching_leaves as f64 / walker.total_leaves as f64
+    };
+    let identical = walker.differences.is_empty();
+
+    let linked_id = format!(
+        "{}:{}",
+        &file1.content_hash[..16.min(file1.content_hash.len())],
+        &file2.content_hash[..16.min(file2.content_hash.len())]
+    );
+
+    Ok(JsonComparisonResult {
+        linked_id,
+        file1_path: file1.path.display().to_string(),
+        file2_path: file2.path.display().to_string(),
+        total_leaf_nodes: walker.total_leaves,
+        matching_leaf_nodes: walker.matching_leaves,
+        differences: walker.differences,
+        similarity_score,
+        identical,
+    })
+}
+
+/// Recursive structural walk accumulating typed differences and leaf counts.
+struct Walker {
+    unordered_arrays: bool,
+    differences: Vec<JsonDifference>,
+    total_leaves: usize,
+    matching_leaves: usize,
+}
+
+impl Walker {
+    fn walk(&mut self, a: &Value, b: &Value, path: &str) {
+        match (a, b) {
+            (Value::Object(ma), Value::Object(mb)) => {
+                // Union of keys, compared as sets.
+                let mut keys: Vec<&String> = ma.keys().chain(mb.keys()).collect();
+                keys.sort_unstable();
+                keys.dedup();
+                for key in keys {
+                    let child_path = format!("{}/{}", path, escape_token(key));
+                    match (ma.get(key), mb.get(key)) {
+                        (Some(va), Some(vb)) => self.walk(va, vb, &child_path),
+                        (Some(va), None) => self.record_missing(va, &child_path, JsonDiffKind::KeyOnlyInFile1),
+                        (None, Some(vb)) => self.record_missing(vb, &child_path, JsonDiffKind::KeyOnlyInFile2),
+                        (None, None) => {}
+                    }
+                }
+            }
+            (Value::Array(aa), Value::Array(ab)) if self.unordered_arrays => {
+                self.walk_array_unordered(aa, ab, path);
+            }
+            (Value::Array(aa), Value::Array(ab)) => {
+                let max = aa.len().max(ab.len());
+                for i in 0..max {
+                    let child_path = format!("{}/{}", path, i);
+                    match (aa.get(i), ab.get(i)) {
+                        (Some(va), Some(vb)) => self.walk(va, vb, &child_path),
+                        (Some(va), None) => self.record_missing(va, &child_path, JsonDiffKind::KeyOnlyInFile1),
+                        (None, Some(vb)) => self.record_missing(vb, &child_path, JsonDiffKind::KeyOnlyInFile2),
+                        (None, None) => {}
+                    }
+                }
+            }
+            // Two scalars (or mismatched container/scalar) — treat as a leaf.
+            _ => {
+                self.total_leaves += 1;
+                if a == b {
+                    self.matching_leaves += 1;
+                } else {
+                    self.differences.push(JsonDifference {
+                        path: path.to_string(),
+                        kind: JsonDiffKind::ValueMismatch,
+                        value1: Some(scalar_repr(a)),
+                        value2: Some(scalar_repr(b)),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Match array elements by best structural similarity, then diff each pair.
+    fn walk_array_unordered(&mut self, aa: &[Value], ab: &[Value], path: &str) {
+        let mut used_b = vec![false; ab.len()];
+        for (i, va) in aa.iter().enumerate() {
+            // Pick the most similar not-yet-used element in b.
+            let best = ab
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| !used_b[*j])
+                .max_by(|(_, x), (_, y)| {
+                    value_similarity(va, x)
+                        .partial_cmp(&value_similarity(va, y))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+            let child_path = format!("{}/{}", path, i);
+            match best {
+                Some((j, vb)) => {
+                    used_b[j] = true;
+                    self.walk(va, vb, &child_path);
+                }
+                None => self.record_missing(va, &child_path, JsonDiffKind::KeyOnlyInFile1),
+            }
+        }
+        // Any unmatched elements in b are extra.
+        for (j, vb) in ab.iter().enumerate() {
+            if !used_b[j] {
+                let child_path = format!("{}/{}", path, j);
+                self.record_missing(vb, &child_path, JsonDiffKind::KeyOnlyInFile2);
+            }
+        }
+    }
+
+    /// Record a subtree present in only one document; its leaves count toward
+    /// the total but never toward matches.
+    fn record_missing(&mut self, value: &Value, path: &str, kind: JsonDiffKind) {
+        self.total_leaves += leaf_count(value);
+        let repr = scalar_repr(value);
+        let (value1, value2) = match kind {
+            JsonDiffKind::KeyOnlyInFile1 => (Some(repr), None),
+            JsonDiffKind::KeyOnlyInFile2 => (None, Some(repr)),
+            JsonDiffKind::ValueMismatch => (Some(repr.clone()), Some(repr)),
+        };
+        self.differences.push(JsonDifference {
+            path: path.to_string(),
+            kind,
+            value1,
+            value2,
+        });
+    }
+}
+
+/// Escape `~` and `/` per RFC 6901 so object keys are valid JSON-pointer tokens.
+fn escape_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Count the scalar leaves in a value (containers recurse, empties count as 1).
+fn leaf_count(value: &Value) -> usize {
+    match value {
+        Value::Object(m) if !m.is_empty() => m.values().map(leaf_count).sum(),
+        Value::Array(a) if !a.is_empty() => a.iter().map(leaf_count).sum(),
+        _ => 1,
+    }
+}
+
+/// Fraction of leaves that match between two values, used to pair array
+/// elements in order-insensitive mode.
+fn value_similarity(a: &Value, b: &Value) -> f64 {
+    let mut walker = Walker {
+        unordered_arrays: true,
+        differences: Vec::new(),
+        total_leaves: 0,
+        matching_leaves: 0,
+    };
+    walker.walk(a, b, "");
+    if walker.total_leaves == 0 {
+        1.0
+    } else {
+        walker.matching_leaves as f64 / walker.total_leaves as f64
+    }
+}
+
+/// Render a value compactly for a mismatch sample.
+fn scalar_repr(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Serialize a JSON comparison result as a pretty mismatch artifact.
+pub fn generate_json_artifact(result: &JsonComparisonResult) -> String {
+    serde_json::to_string_pretty(result).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walk(a: &str, b: &str, unordered: bool) -> Walker {
+        let va: Value = serde_json::from_str(a).unwrap();
+        let vb: Value = serde_json::from_str(b).unwrap();
+        let mut w = Walker {
+            unordered_arrays: unordered,
+            differences: Vec::new(),
+            total_leaves: 0,
+            matching_leaves: 0,
+        };
+        w.walk(&va, &vb, "");
+        w
+    }
+
+    #[test]
+    fn test_value_mismatch_path() {
+        let w = walk(r#"{"items":[{"name":"a"}]}"#, r#"{"items":[{"name":"b"}]}"#, false);
+        assert_eq!(w.differences.len(), 1);
+        assert_eq!(w.differences[0].path, "/items/0/name");
+        assert_eq!(w.total_leaves, 1);
+        assert_eq!(w.matching_leaves, 0);
+    }
+
+    #[test]
+    fn test_reordered_object_keys_match() {
+        let w = walk(r#"{"a":1,"b":2}"#, r#"{"b":2,"a":1}"#, false);
+        assert!(w.differences.is_empty());
+        assert_eq!(w.matching_leaves, 2);
+    }
+
+    #[test]
+    fn test_unordered_array() {
+        let w = walk(r#"[{"id":1},{"id":2}]"#, r#"[{"id":2},{"id":1}]"#, true);
+        assert!(w.differences.is_empty());
+    }
+}
@@ -4,12 +4,13 @@
 //! with per-column mismatch statistics and numeric tolerance support.
 
 use crate::types::{
-    ColumnMismatch, CompareConfig, FieldMismatch, FileEntry, FileType, StructuredComparisonResult,
+    ColumnMismatch, CompareConfig, FieldMismatch, FileEncoding, FileEntry, FileType, FuzzyMatch,
+    MatchingRule, StructuredComparisonResult,
 };
 use anyhow::{Context, Result};
 use csv::ReaderBuilder;
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
+use strsim::levenshtein;
 
 /// Compare two structured files (CSV/TSV)
 pub fn compare_structured_files(
@@ -22,8 +23,10 @@ pub fn compare_structured_files(
     let delimiter2 = get_delimiter(&file2.file_type);
 
     // Parse both files
-    let (headers1, records1) = parse_structured_file(&file1.path, delimiter1, &config.key_columns)?;
-    let (headers2, records2) = parse_structured_file(&file2.path, delimiter2, &config.key_columns)?;
+    let (headers1, records1) =
+        parse_structured_file(&file1.path, delimiter1, &config.key_columns, config.encoding)?;
+    let (headers2, records2) =
+        parse_structured_file(&file2.path, delimiter2, &config.key_columns, config.encoding)?;
 
     // Analyze columns
     let columns1: HashSet<&str> = headers1.iter().map(|s| s.as_str()).collect();
@@ -66,11 +69,17 @@ pub fn compare_structured_files(
             let val1 = row1.get(col).map(|s| s.as_str()).unwrap_or("");
             let val2 = row2.get(col).map(|s| s.as_str()).unwrap_or("");
 
-            if !values_equal(val1, val2, config.numeric_tolerance) {
+            let rule = config
+                .matching_rules
+                .get(col)
+                .unwrap_or(&config.default_matching_rule);
+
+            if !rule_matches(rule, val1, val2, config.numeric_tolerance) {
                 field_mismatches.entry(col.clone()).or_default().push(FieldMismatch {
                     key: key.to_string(),
                     value1: val1.to_string(),
                     value2: val2.to_string(),
+                    diff_chunks: crate::cell_diff::diff_cell(val1, val2),
                 });
             }
         }
@@ -97,10 +106,65 @@ pub fn compare_structured_files(
 
     let total_field_mismatches: usize = column_mismatches.iter().map(|c| c.mismatch_count).sum();
 
-    // Calculate similarity score
-    let total_records = keys1.len() + keys2.len() - common_keys.len();
+    // Optional fuzzy linkage: pair up leftover keys that differ only by a small
+    // edit (typo/reformatting) so they stop looking like a delete+insert pair.
+    let mut fuzzy_matches: Vec<FuzzyMatch> = Vec::new();
+    let mut fuzzy_matched1: HashSet<&str> = HashSet::new();
+    let mut fuzzy_matched2: HashSet<&str> = HashSet::new();
+
+    if let Some(radius) = config.fuzzy_key_radius {
+        let mut tree = KeyBkTree::new();
+        for &k in &only_in_file2_keys {
+            tree.insert(k);
+        }
+
+        for &k1 in &only_in_file1_keys {
+            if let Some((k2, dist)) = tree.nearest(k1, radius, &fuzzy_matched2) {
+                fuzzy_matched1.insert(k1);
+                fuzzy_matched2.insert(k2);
+
+                let row1 = records1.get(k1).unwrap();
+                let row2 = records2.get(k2).unwrap();
+                let mut linked_mismatches = Vec::new();
+                for col in &common_columns {
+                    if config.key_columns.contains(col) {
+                        continue;
+                    }
+                    let val1 = row1.get(col).map(|s| s.as_str()).unwrap_or("");
+                    let val2 = row2.get(col).map(|s| s.as_str()).unwrap_or("");
+                    let rule = config
+                        .matching_rules
+                        .get(col)
+                        .unwrap_or(&config.default_matching_rule);
+                    if !rule_matches(rule, val1, val2, config.numeric_tolerance) {
+                        linked_mismatches.push(FieldMismatch {
+                            key: k1.to_string(),
+                            value1: val1.to_string(),
+                            value2: val2.to_string(),
+                            diff_chunks: crate::cell_diff::diff_cell(val1, val2),
+                        });
+                    }
+                }
+
+                fuzzy_matches.push(FuzzyMatch {
+                    key1: k1.to_string(),
+                    key2: k2.to_string(),
+                    key_distance: dist,
+                    field_mismatches: linked_mismatches,
+                });
+            }
+        }
+    }
+
+    // Keys linked fuzzily no longer count as exclusive to one side.
+    let only_in_file1 = only_in_file1_keys.len() - fuzzy_matched1.len();
+    let only_in_file2 = only_in_file2_keys.len() - fuzzy_matched2.len();
+
+    // Calculate similarity score; fuzzily-linked rows count as linked records.
+    let linked = common_keys.len() + fuzzy_matches.len();
+    let total_records = keys1.len() + keys2.len() - linked;
     let similarity_score = if total_records > 0 {
-        common_keys.len() as f64 / total_records as f64
+        linked as f64 / total_records as f64
     } else {
         1.0
     };
@@ -116,6 +180,15 @@ pub fn compare_structured_files(
         && only_in_file2_keys.is_empty()
         && total_field_mismatches == 0;
 
+    // Schema-level overlap, independent of the record comparison: even when the
+    // rows look unrelated, a high score flags an evolved version of the table.
+    let schema_similarity =
+        crate::fingerprint::schema_similarity(&headers1, &headers2, &config.normalization);
+    let schema_containment_file1 =
+        crate::fingerprint::schema_containment(&headers1, &headers2, &config.normalization);
+    let schema_containment_file2 =
+        crate::fingerprint::schema_containment(&headers2, &headers1, &config.normalization);
+
     Ok(StructuredComparisonResult {
         linked_id,
         file1_path: file1.path.display().to_string(),
@@ -123,18 +196,99 @@ pub fn compare_structured_files(
         file1_row_count: records1.len(),
         file2_row_count: records2.len(),
         common_records: common_keys.len(),
-        only_in_file1: only_in_file1_keys.len(),
-        only_in_file2: only_in_file2_keys.len(),
+        only_in_file1,
+        only_in_file2,
         similarity_score,
         field_mismatches: column_mismatches,
         total_field_mismatches,
+        fuzzy_matches,
         columns_only_in_file1,
         columns_only_in_file2,
         common_columns,
+        schema_similarity,
+        schema_containment_file1,
+        schema_containment_file2,
         identical,
     })
 }
 
+/// A BK-tree over string keys using Levenshtein distance as the metric.
+///
+/// Used to pair leftover record keys that differ only by a small edit without
+/// an O(n*m) scan: the triangle inequality lets each query prune whole subtrees
+/// whose edge distance puts them outside the search radius.
+struct KeyBkTree<'a> {
+    root: Option<KeyNode<'a>>,
+}
+
+struct KeyNode<'a> {
+    key: &'a str,
+    children: HashMap<usize, KeyNode<'a>>,
+}
+
+impl<'a> KeyBkTree<'a> {
+    fn new() -> Self {
+        KeyBkTree { root: None }
+    }
+
+    fn insert(&mut self, key: &'a str) {
+        match &mut self.root {
+            None => self.root = Some(KeyNode { key, children: HashMap::new() }),
+            Some(root) => {
+                let mut node = root;
+                loop {
+                    let d = levenshtein(node.key, key);
+                    if d == 0 {
+                        // Duplicate key already present; nothing to add.
+                        return;
+                    }
+                    if node.children.contains_key(&d) {
+                        node = node.children.get_mut(&d).unwrap();
+                    } else {
+                        node.children.insert(d, KeyNode { key, children: HashMap::new() });
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Find the nearest key within `radius` that is not already claimed,
+    /// returning it together with its edit distance from `query`.
+    fn nearest(
+        &self,
+        query: &str,
+        radius: u32,
+        claimed: &HashSet<&str>,
+    ) -> Option<(&'a str, u32)> {
+        let root = self.root.as_ref()?;
+        let radius = radius as usize;
+        let mut best: Option<(&'a str, usize)> = None;
+        let mut stack = vec![root];
+
+        while let Some(node) = stack.pop() {
+            let d = levenshtein(node.key, query);
+            if d <= radius && !claimed.contains(node.key) {
+                match best {
+                    Some((_, bd)) if bd <= d => {}
+                    _ => best = Some((node.key, d)),
+                }
+            }
+            // Only subtrees whose edge distance lands within [d-radius, d+radius]
+            // can contain a match.
+            let lo = d.saturating_sub(radius);
+            let hi = d + radius;
+            for (&edge, child) in &node.children {
+                if edge >= lo && edge <= hi {
+                    stack.push(child);
+                }
+            }
+        }
+
+        best.map(|(k, d)| (k, d as u32))
+    }
+}
+
 /// Get the appropriate delimiter for a file type
 fn get_delimiter(file_type: &FileType) -> u8 {
     match file_type {
@@ -148,14 +302,16 @@ fn parse_structured_file(
     path: &std::path::Path,
     delimiter: u8,
     key_columns: &[String],
+    encoding: FileEncoding,
 ) -> Result<(Vec<String>, HashMap<String, HashMap<String, String>>)> {
-    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let raw = std::fs::read(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let text = decode_text(&raw, encoding);
 
     let mut reader = ReaderBuilder::new()
         .delimiter(delimiter)
         .has_headers(true)
         .flexible(true)
-        .from_reader(file);
+        .from_reader(std::io::Cursor::new(text.into_bytes()));
 
     // Get headers
     let headers: Vec<String> = reader
@@ -202,6 +358,90 @@ fn parse_structured_file(
     Ok((headers, records))
 }
 
+/// Decode raw file bytes to UTF-8 text, stripping any leading BOM and
+/// transcoding legacy single-byte encodings as requested.
+///
+/// A UTF-16 BOM always wins (exports from some tools are UTF-16LE); otherwise a
+/// UTF-8 BOM is stripped so it can't fold into the first header name, and the
+/// remaining bytes are decoded with the configured encoding.
+fn decode_text(raw: &[u8], encoding: FileEncoding) -> String {
+    use encoding_rs::{UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+
+    if raw.starts_with(&[0xFF, 0xFE]) {
+        return UTF_16LE.decode(raw).0.into_owned();
+    }
+    if raw.starts_with(&[0xFE, 0xFF]) {
+        return UTF_16BE.decode(raw).0.into_owned();
+    }
+
+    let body = raw.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(raw);
+
+    match encoding {
+        FileEncoding::Auto | FileEncoding::Utf8 => UTF_8.decode(body).0.into_owned(),
+        // Latin-1 maps each byte directly onto U+0000..=U+00FF.
+        FileEncoding::Latin1 => body.iter().map(|&b| b as char).collect(),
+        FileEncoding::Windows1252 => WINDOWS_1252.decode(body).0.into_owned(),
+    }
+}
+
+/// Apply a [`MatchingRule`] to a pair of cell values.
+///
+/// Rules that require parsing (number/date/regex) count as a mismatch when
+/// either side fails to parse, so a malformed value never silently matches.
+fn rule_matches(rule: &MatchingRule, val1: &str, val2: &str, tolerance: f64) -> bool {
+    match rule {
+        MatchingRule::Equality => values_equal(val1, val2, tolerance),
+        MatchingRule::IgnoreCase => val1.eq_ignore_ascii_case(val2),
+        MatchingRule::Type => value_kind(val1) == value_kind(val2),
+        MatchingRule::Number | MatchingRule::Decimal => {
+            match (val1.parse::<f64>(), val2.parse::<f64>()) {
+                (Ok(_), Ok(_)) => values_equal(val1, val2, tolerance),
+                _ => false,
+            }
+        }
+        MatchingRule::Integer => match (val1.parse::<i64>(), val2.parse::<i64>()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        },
+        MatchingRule::Regex(pattern) => match regex::Regex::new(pattern) {
+            Ok(re) => re.is_match(val1) && re.is_match(val2),
+            Err(_) => false,
+        },
+        MatchingRule::Timestamp(fmt) => {
+            match (
+                chrono::NaiveDateTime::parse_from_str(val1, fmt),
+                chrono::NaiveDateTime::parse_from_str(val2, fmt),
+            ) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => false,
+            }
+        }
+        MatchingRule::Date(fmt) => match (
+            chrono::NaiveDate::parse_from_str(val1, fmt),
+            chrono::NaiveDate::parse_from_str(val2, fmt),
+        ) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        },
+        MatchingRule::Include(substr) => val1.contains(substr.as_str()) && val2.contains(substr.as_str()),
+        MatchingRule::MinLength(n) => val1.chars().count() >= *n && val2.chars().count() >= *n,
+        MatchingRule::MaxLength(n) => val1.chars().count() <= *n && val2.chars().count() <= *n,
+    }
+}
+
+/// Classify a cell value into a coarse kind for [`MatchingRule::Type`].
+fn value_kind(val: &str) -> &'static str {
+    if val.parse::<i64>().is_ok() {
+        "int"
+    } else if val.parse::<f64>().is_ok() {
+        "float"
+    } else if matches!(val.to_ascii_lowercase().as_str(), "true" | "false") {
+        "bool"
+    } else {
+        "string"
+    }
+}
+
 /// Check if two string values are equal, with numeric tolerance support
 fn values_equal(val1: &str, val2: &str, tolerance: f64) -> bool {
     // Direct string comparison first
@@ -245,4 +485,36 @@ mod tests {
         assert!(values_equal("1.0000", "1.0001", 0.001));
         assert!(!values_equal("1.0", "2.0", 0.0001));
     }
+
+    #[test]
+    fn test_decode_text_strips_bom() {
+        // UTF-8 BOM is stripped so it can't fold into the first header.
+        let utf8 = [0xEF, 0xBB, 0xBF, b'i', b'd'];
+        assert_eq!(decode_text(&utf8, FileEncoding::Auto), "id");
+
+        // UTF-16LE BOM forces a UTF-16 decode regardless of the setting.
+        let utf16le = [0xFF, 0xFE, b'i', 0x00, b'd', 0x00];
+        assert_eq!(decode_text(&utf16le, FileEncoding::Utf8), "id");
+
+        // Latin-1 high bytes map onto the matching code points.
+        assert_eq!(decode_text(&[0xE9], FileEncoding::Latin1), "é");
+    }
+
+    #[test]
+    fn test_key_bktree_nearest() {
+        let mut tree = KeyBkTree::new();
+        for k in ["alice|2023", "bob|2023", "carol|2024"] {
+            tree.insert(k);
+        }
+        let claimed = HashSet::new();
+
+        // A single-edit typo is found within radius 1.
+        assert_eq!(tree.nearest("alive|2023", 1, &claimed), Some(("alice|2023", 1)));
+        // Nothing within the radius yields no match.
+        assert_eq!(tree.nearest("zzz", 1, &claimed), None);
+
+        // Already-claimed keys are skipped.
+        let claimed: HashSet<&str> = ["alice|2023"].into_iter().collect();
+        assert_eq!(tree.nearest("alive|2023", 1, &claimed), None);
+    }
 }
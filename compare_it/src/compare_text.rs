@@ -16,6 +16,26 @@ pub fn compare_text_files(
     file2: &FileEntry,
     config: &CompareConfig,
 ) -> Result<TextComparisonResult> {
+    // Structural (AST-aware) comparison short-circuits the line-based diff when
+    // a tree-sitter grammar exists for the files; otherwise it falls through to
+    // the line-level `Diff` path below.
+    if config.similarity_algorithm == SimilarityAlgorithm::Structural {
+        if let (Ok(src1), Ok(src2)) = (
+            std::fs::read_to_string(&file1.path),
+            std::fs::read_to_string(&file2.path),
+        ) {
+            if let Some(sdiff) = crate::structural::structural_diff(
+                &file1.extension,
+                &src1,
+                &file2.extension,
+                &src2,
+                config.max_diff_bytes,
+            ) {
+                return Ok(build_structural_result(file1, file2, &src1, &src2, sdiff));
+            }
+        }
+    }
+
     // Read and normalize content
     let lines1 = read_normalized_lines(&file1.path, &config.normalization)?;
     let lines2 = read_normalized_lines(&file2.path, &config.normalization)?;
@@ -86,6 +106,16 @@ pub fn compare_text_files(
             }
         }
         SimilarityAlgorithm::CharJaro => jaro_winkler(&text1, &text2),
+        // Reached only when no grammar was available for the files; score the
+        // line-level diff just like `Diff`.
+        SimilarityAlgorithm::Structural => {
+            let total = common_lines + only_in_file1 + only_in_file2;
+            if total > 0 {
+                common_lines as f64 / total as f64
+            } else {
+                1.0
+            }
+        }
     };
 
     // Generate unified diff format
@@ -95,6 +125,7 @@ pub fn compare_text_files(
         &text1,
         &text2,
         config.max_diff_bytes,
+        config.inline_char_diff,
     );
 
     // Create linked ID
@@ -130,13 +161,59 @@ pub fn compare_text_files(
     })
 }
 
-/// Generate unified diff format output
+/// Assemble a [`TextComparisonResult`] from a structural (token-level) diff,
+/// mapping changed tokens back to file-1 line ranges for `different_positions`.
+fn build_structural_result(
+    file1: &FileEntry,
+    file2: &FileEntry,
+    src1: &str,
+    src2: &str,
+    sdiff: crate::structural::StructuralDiff,
+) -> TextComparisonResult {
+    let linked_id = format!(
+        "{}:{}",
+        &file1.content_hash[..16.min(file1.content_hash.len())],
+        &file2.content_hash[..16.min(file2.content_hash.len())]
+    );
+
+    let identical = sdiff.only_in_file1 == 0 && sdiff.only_in_file2 == 0;
+    let positions_str = encode_ranges(&sdiff.changed_lines);
+
+    TextComparisonResult {
+        linked_id,
+        file1_path: file1.path.display().to_string(),
+        file2_path: file2.path.display().to_string(),
+        file1_line_count: src1.lines().count(),
+        file2_line_count: src2.lines().count(),
+        common_lines: sdiff.common_tokens,
+        only_in_file1: sdiff.only_in_file1,
+        only_in_file2: sdiff.only_in_file2,
+        similarity_score: sdiff.similarity(),
+        different_positions: positions_str,
+        detailed_diff: sdiff.detailed_diff,
+        diff_truncated: false,
+        identical,
+    }
+}
+
+/// Minimum char-level similarity for two lines to be diffed inline rather than
+/// shown as a plain remove-and-re-add pair.
+const INLINE_SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// Generate unified diff format output.
+///
+/// When `inline` is set, consecutive delete/insert lines within a hunk are
+/// paired and diffed at the character level, so a one-character edit renders as
+/// a single `~`-marked line `~prefix[-old-]{+new+}suffix` instead of a whole
+/// removed-and-re-added line.
+/// Lines that share too little to diff cleanly fall back to the plain pair.
 fn generate_unified_diff(
     file1_name: &str,
     file2_name: &str,
     text1: &str,
     text2: &str,
     max_bytes: usize,
+    inline: bool,
 ) -> (String, bool) {
     let diff = TextDiff::from_lines(text1, text2);
 
@@ -155,22 +232,33 @@ fn generate_unified_diff(
         }
 
         let _ = writeln!(output, "{}", hunk.header());
+
+        // Buffer runs of deletes/inserts so they can be paired for inline diff.
+        let mut deletes: Vec<String> = Vec::new();
+        let mut inserts: Vec<String> = Vec::new();
+
         for change in hunk.iter_changes() {
+            match change.tag() {
+                ChangeTag::Delete => deletes.push(change.value().to_string()),
+                ChangeTag::Insert => inserts.push(change.value().to_string()),
+                ChangeTag::Equal => {
+                    flush_change_run(&mut output, &deletes, &inserts, inline);
+                    deletes.clear();
+                    inserts.clear();
+                    let _ = write!(output, " {}", change.value());
+                    if !change.value().ends_with('\n') {
+                        output.push('\n');
+                    }
+                }
+            }
+
             if output.len() >= max_bytes {
                 truncated = true;
                 break;
             }
-
-            let prefix = match change.tag() {
-                ChangeTag::Delete => "-",
-                ChangeTag::Insert => "+",
-                ChangeTag::Equal => " ",
-            };
-            let _ = write!(output, "{}{}", prefix, change.value());
-            if !change.value().ends_with('\n') {
-                output.push('\n');
-            }
         }
+
+        flush_change_run(&mut output, &deletes, &inserts, inline);
     }
 
     if truncated {
@@ -180,6 +268,71 @@ fn generate_unified_diff(
     (output, truncated)
 }
 
+/// Emit a buffered run of delete/insert lines, pairing them for inline diff.
+fn flush_change_run(output: &mut String, deletes: &[String], inserts: &[String], inline: bool) {
+    let paired = deletes.len().min(inserts.len());
+
+    for i in 0..paired {
+        if inline {
+            if let Some(rendered) = render_inline_pair(&deletes[i], &inserts[i]) {
+                output.push_str(&rendered);
+                if !rendered.ends_with('\n') {
+                    output.push('\n');
+                }
+                continue;
+            }
+        }
+        write_plain_line(output, '-', &deletes[i]);
+        write_plain_line(output, '+', &inserts[i]);
+    }
+
+    // Any unpaired extras render plainly.
+    for line in &deletes[paired..] {
+        write_plain_line(output, '-', line);
+    }
+    for line in &inserts[paired..] {
+        write_plain_line(output, '+', line);
+    }
+}
+
+/// Write a single prefixed diff line, ensuring a trailing newline.
+fn write_plain_line(output: &mut String, prefix: char, value: &str) {
+    let _ = write!(output, "{}{}", prefix, value);
+    if !value.ends_with('\n') {
+        output.push('\n');
+    }
+}
+
+/// Render a changed line pair at the character level, or `None` when the two
+/// lines are too dissimilar to highlight cleanly.
+fn render_inline_pair(old: &str, new: &str) -> Option<String> {
+    let old_trimmed = old.strip_suffix('\n').unwrap_or(old);
+    let new_trimmed = new.strip_suffix('\n').unwrap_or(new);
+
+    let char_diff = TextDiff::from_chars(old_trimmed, new_trimmed);
+    if char_diff.ratio() < INLINE_SIMILARITY_THRESHOLD {
+        return None;
+    }
+
+    let mut rendered = String::from("~");
+    for change in char_diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Equal => rendered.push_str(change.value()),
+            ChangeTag::Delete => {
+                rendered.push_str("[-");
+                rendered.push_str(change.value());
+                rendered.push_str("-]");
+            }
+            ChangeTag::Insert => {
+                rendered.push_str("{+");
+                rendered.push_str(change.value());
+                rendered.push_str("+}");
+            }
+        }
+    }
+    Some(rendered)
+}
+
 /// Encode a list of positions as ranges (e.g., "1-5,8,10-15")
 fn encode_ranges(positions: &[usize]) -> String {
     if positions.is_empty() {
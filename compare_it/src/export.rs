@@ -7,9 +7,20 @@
 
 use crate::types::{ComparisonResult, ComparisonSummary};
 use anyhow::{Context, Result};
+use serde::Serialize;
 use std::fs::{self, File};
 use std::io::{BufWriter, Write};
 use std::path::Path;
+use tera::Tera;
+
+/// Serialization layout for the primary JSON output file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonFormat {
+    /// One compact JSON object per line (ideal for streaming consumers).
+    Jsonl,
+    /// A single JSON array document; `pretty` turns on indentation.
+    Array { pretty: bool },
+}
 
 /// Export results to JSONL format (one JSON object per line)
 pub fn export_jsonl(results: &[ComparisonResult], output_path: &Path) -> Result<()> {
@@ -26,6 +37,101 @@ pub fn export_jsonl(results: &[ComparisonResult], output_path: &Path) -> Result<
     Ok(())
 }
 
+/// Export results as a single JSON array document.
+///
+/// The brackets, element separators, and elements are written incrementally so
+/// the full result set is never buffered as one serialized string in memory.
+/// With `pretty` set, each element is indented and placed on its own line.
+pub fn export_json_array(
+    results: &[ComparisonResult],
+    output_path: &Path,
+    pretty: bool,
+) -> Result<()> {
+    let file = File::create(output_path)
+        .with_context(|| format!("Failed to create {}", output_path.display()))?;
+    let mut writer = BufWriter::new(file);
+
+    if pretty {
+        writeln!(writer, "[")?;
+        for (i, result) in results.iter().enumerate() {
+            let json = serde_json::to_string_pretty(result)?;
+            // Indent every line of the element by two spaces.
+            for (j, line) in json.lines().enumerate() {
+                if j > 0 {
+                    writeln!(writer)?;
+                }
+                write!(writer, "  {}", line)?;
+            }
+            if i + 1 < results.len() {
+                writeln!(writer, ",")?;
+            } else {
+                writeln!(writer)?;
+            }
+        }
+        writeln!(writer, "]")?;
+    } else {
+        write!(writer, "[")?;
+        for (i, result) in results.iter().enumerate() {
+            if i > 0 {
+                write!(writer, ",")?;
+            }
+            let json = serde_json::to_string(result)?;
+            write!(writer, "{}", json)?;
+        }
+        writeln!(writer, "]")?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Export results to the primary JSON output file in the requested `format`.
+pub fn export_json(
+    results: &[ComparisonResult],
+    output_path: &Path,
+    format: JsonFormat,
+) -> Result<()> {
+    match format {
+        JsonFormat::Jsonl => export_jsonl(results, output_path),
+        JsonFormat::Array { pretty } => export_json_array(results, output_path, pretty),
+    }
+}
+
+/// Export the summary and full result list as one top-level JSON document.
+///
+/// The layout is `{ "summary": ComparisonSummary, "results": [ComparisonResult...] }`,
+/// which gives CI pipelines a single machine-readable artifact bundling the
+/// aggregate counts with the per-pair detail. With `compact` set the document is
+/// emitted without pretty-printing. This complements the streaming JSONL export
+/// rather than replacing it.
+pub fn export_combined_json(
+    results: &[ComparisonResult],
+    summary: &ComparisonSummary,
+    output_path: &Path,
+    compact: bool,
+) -> Result<()> {
+    #[derive(Serialize)]
+    struct CombinedReport<'a> {
+        summary: &'a ComparisonSummary,
+        results: &'a [ComparisonResult],
+    }
+
+    let report = CombinedReport { summary, results };
+    let json = if compact {
+        serde_json::to_string(&report)?
+    } else {
+        serde_json::to_string_pretty(&report)?
+    };
+
+    let file = File::create(output_path)
+        .with_context(|| format!("Failed to create {}", output_path.display()))?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(json.as_bytes())?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
 /// Export results to CSV summary format
 pub fn export_csv(results: &[ComparisonResult], output_path: &Path) -> Result<()> {
     let file = File::create(output_path)
@@ -83,6 +189,48 @@ pub fn export_csv(results: &[ComparisonResult], output_path: &Path) -> Result<()
                     &r.total_field_mismatches.to_string(),
                 ])?;
             }
+            ComparisonResult::Json(r) => {
+                writer.write_record([
+                    &r.linked_id,
+                    &r.file1_path,
+                    &r.file2_path,
+                    "json",
+                    &format!("{:.4}", r.similarity_score),
+                    &r.identical.to_string(),
+                    &r.total_leaf_nodes.to_string(),
+                    &r.total_leaf_nodes.to_string(),
+                    &r.matching_leaf_nodes.to_string(),
+                    &r
+                        .differences
+                        .iter()
+                        .filter(|d| matches!(d.kind, crate::types::JsonDiffKind::KeyOnlyInFile1))
+                        .count()
+                        .to_string(),
+                    &r
+                        .differences
+                        .iter()
+                        .filter(|d| matches!(d.kind, crate::types::JsonDiffKind::KeyOnlyInFile2))
+                        .count()
+                        .to_string(),
+                    &r.differences.len().to_string(),
+                ])?;
+            }
+            ComparisonResult::Image(r) => {
+                writer.write_record([
+                    &r.linked_id,
+                    &r.file1_path,
+                    &r.file2_path,
+                    "image",
+                    &format!("{:.4}", r.similarity_score),
+                    &r.identical.to_string(),
+                    "",
+                    "",
+                    &r.verdict,
+                    "",
+                    "",
+                    &r.hamming_distance.to_string(),
+                ])?;
+            }
             ComparisonResult::HashOnly {
                 linked_id,
                 file1_path,
@@ -136,6 +284,254 @@ pub fn export_csv(results: &[ComparisonResult], output_path: &Path) -> Result<()
     Ok(())
 }
 
+/// A single diff line tagged for colorized rendering.
+#[derive(Serialize)]
+struct DiffLine {
+    /// CSS class: `add`, `del`, or `ctx`.
+    class: &'static str,
+    text: String,
+}
+
+/// A table row plus collapsible detail, rendered from one `ComparisonResult`.
+#[derive(Serialize)]
+struct ReportRow {
+    status: &'static str,
+    file1: String,
+    file2: String,
+    similarity_pct: f64,
+    sim_class: &'static str,
+    kind: &'static str,
+    common: String,
+    only1: String,
+    only2: String,
+    diff_lines: Vec<DiffLine>,
+}
+
+/// Embedded Tera template for the self-contained HTML report.
+const HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<title>CompareIt Report</title>
+<style>
+:root{--bg:#0d1117;--bg2:#161b22;--bg3:#21262d;--fg:#c9d1d9;--fg2:#8b949e;--accent:#58a6ff;--ok:#3fb950;--warn:#d29922;--err:#f85149;--border:#30363d;}
+*{box-sizing:border-box;margin:0;padding:0;}
+body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;background:var(--bg);color:var(--fg);line-height:1.6;padding:2rem;}
+.container{max-width:1400px;margin:0 auto;}
+h1{font-size:2rem;font-weight:600;margin-bottom:.5rem;}
+.subtitle{color:var(--fg2);margin-bottom:2rem;}
+.summary-grid{display:grid;grid-template-columns:repeat(auto-fit,minmax(160px,1fr));gap:1rem;margin-bottom:2rem;}
+.card{background:var(--bg2);border:1px solid var(--border);border-radius:6px;padding:1rem;}
+.card .label{font-size:.75rem;color:var(--fg2);text-transform:uppercase;letter-spacing:.05em;}
+.card .value{font-size:1.5rem;font-weight:600;margin-top:.25rem;}
+.value.ok{color:var(--ok);}.value.warn{color:var(--warn);}.value.err{color:var(--err);}
+table{width:100%;border-collapse:collapse;font-size:.875rem;background:var(--bg2);border:1px solid var(--border);border-radius:6px;overflow:hidden;}
+th,td{padding:.6rem 1rem;text-align:left;border-bottom:1px solid var(--border);}
+th{background:var(--bg3);font-weight:600;color:var(--fg2);cursor:pointer;user-select:none;}
+tr:hover{background:var(--bg3);}
+.badge{display:inline-block;padding:.125rem .5rem;border-radius:9999px;font-size:.75rem;}
+.badge.identical{background:rgba(63,185,80,.2);color:var(--ok);}
+.badge.different{background:rgba(210,153,34,.2);color:var(--warn);}
+.badge.error{background:rgba(248,81,73,.2);color:var(--err);}
+details{background:var(--bg2);border:1px solid var(--border);border-radius:6px;margin-top:.5rem;padding:.5rem 1rem;}
+summary{cursor:pointer;color:var(--accent);}
+pre{margin-top:.5rem;overflow-x:auto;font-family:ui-monospace,Menlo,Consolas,monospace;font-size:.8125rem;}
+.add{color:var(--ok);}.del{color:var(--err);}.ctx{color:var(--fg2);}
+</style>
+</head>
+<body>
+<div class="container">
+<h1>CompareIt Report</h1>
+<p class="subtitle">File comparison analysis</p>
+<div class="summary-grid">
+<div class="card"><div class="label">Pairs Compared</div><div class="value">{{ summary.pairs_compared }}</div></div>
+<div class="card"><div class="label">Identical</div><div class="value ok">{{ summary.identical_pairs }}</div></div>
+<div class="card"><div class="label">Different</div><div class="value warn">{{ summary.different_pairs }}</div></div>
+<div class="card"><div class="label">Errors</div><div class="value{% if summary.error_pairs > 0 %} err{% endif %}">{{ summary.error_pairs }}</div></div>
+<div class="card"><div class="label">Avg Similarity</div><div class="value">{{ summary.average_similarity * 100.0 | round(precision=1) }}%</div></div>
+<div class="card"><div class="label">Min / Max</div><div class="value">{{ summary.min_similarity * 100.0 | round(precision=1) }}% / {{ summary.max_similarity * 100.0 | round(precision=1) }}%</div></div>
+</div>
+<table>
+<thead><tr><th>Status</th><th>File 1</th><th>File 2</th><th>Similarity</th><th>Type</th><th>Common</th><th>Only F1</th><th>Only F2</th></tr></thead>
+<tbody>
+{% for row in rows %}
+<tr>
+<td><span class="badge {{ row.status }}">{{ row.status }}</span></td>
+<td>{{ row.file1 }}</td>
+<td>{{ row.file2 }}</td>
+<td>{{ row.similarity_pct | round(precision=1) }}%</td>
+<td>{{ row.kind }}</td>
+<td>{{ row.common }}</td>
+<td>{{ row.only1 }}</td>
+<td>{{ row.only2 }}</td>
+</tr>
+{% endfor %}
+</tbody>
+</table>
+{% for row in rows %}{% if row.diff_lines | length > 0 %}
+<details>
+<summary>{{ row.file1 }} ↔ {{ row.file2 }}</summary>
+<pre>{% for line in row.diff_lines %}<span class="{{ line.class }}">{{ line.text }}</span>
+{% endfor %}</pre>
+</details>
+{% endif %}{% endfor %}
+</div>
+<script>
+document.querySelectorAll('th').forEach(function(th){th.addEventListener('click',function(){
+ var tb=th.closest('table').querySelector('tbody');var i=th.cellIndex;
+ var asc=th.dataset.asc!=='1';th.dataset.asc=asc?'1':'0';
+ Array.from(tb.rows).sort(function(a,b){
+  var x=a.cells[i].textContent.trim(),y=b.cells[i].textContent.trim();
+  var nx=parseFloat(x),ny=parseFloat(y);
+  if(!isNaN(nx)&&!isNaN(ny))return asc?nx-ny:ny-nx;
+  return asc?x.localeCompare(y):y.localeCompare(x);
+ }).forEach(function(r){tb.appendChild(r);});
+});});
+</script>
+</body>
+</html>
+"#;
+
+/// Convert a unified-diff string into colorized, tagged lines.
+fn tag_diff_lines(diff: &str) -> Vec<DiffLine> {
+    diff.lines()
+        .map(|line| {
+            let class = match line.chars().next() {
+                Some('+') => "add",
+                Some('-') => "del",
+                _ => "ctx",
+            };
+            DiffLine {
+                class,
+                text: line.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Build the per-result rows consumed by the HTML template.
+///
+/// Rendering depends only on the `ComparisonResult` enum, so it stays fully
+/// decoupled from the comparison logic and every variant renders.
+fn build_report_rows(results: &[ComparisonResult]) -> Vec<ReportRow> {
+    results
+        .iter()
+        .map(|result| {
+            let (file1, file2) = result.file_paths();
+            let similarity = result.similarity_score();
+            let identical = result.is_identical();
+
+            let status = if identical {
+                "identical"
+            } else if matches!(result, ComparisonResult::Error { .. }) {
+                "error"
+            } else {
+                "different"
+            };
+
+            let sim_class = if similarity >= 0.9 {
+                "high"
+            } else if similarity >= 0.5 {
+                "medium"
+            } else {
+                "low"
+            };
+
+            let (kind, common, only1, only2, diff_lines) = match result {
+                ComparisonResult::Text(r) => (
+                    "text",
+                    r.common_lines.to_string(),
+                    r.only_in_file1.to_string(),
+                    r.only_in_file2.to_string(),
+                    tag_diff_lines(&r.detailed_diff),
+                ),
+                ComparisonResult::Structured(r) => (
+                    "csv",
+                    r.common_records.to_string(),
+                    r.only_in_file1.to_string(),
+                    r.only_in_file2.to_string(),
+                    Vec::new(),
+                ),
+                ComparisonResult::Json(r) => (
+                    "json",
+                    r.matching_leaf_nodes.to_string(),
+                    "0".to_string(),
+                    "0".to_string(),
+                    r.differences
+                        .iter()
+                        .map(|d| DiffLine {
+                            class: match d.kind {
+                                crate::types::JsonDiffKind::KeyOnlyInFile1 => "del",
+                                crate::types::JsonDiffKind::KeyOnlyInFile2 => "add",
+                                crate::types::JsonDiffKind::ValueMismatch => "ctx",
+                            },
+                            text: format!(
+                                "{}: {} -> {}",
+                                d.path,
+                                d.value1.as_deref().unwrap_or(""),
+                                d.value2.as_deref().unwrap_or("")
+                            ),
+                        })
+                        .collect(),
+                ),
+                ComparisonResult::Image(r) => (
+                    "image",
+                    format!("d={}", r.hamming_distance),
+                    r.verdict.clone(),
+                    "0".to_string(),
+                    Vec::new(),
+                ),
+                ComparisonResult::HashOnly { identical, .. } => (
+                    "binary",
+                    if *identical { "1" } else { "0" }.to_string(),
+                    "0".to_string(),
+                    "0".to_string(),
+                    Vec::new(),
+                ),
+                ComparisonResult::Error { error, .. } => (
+                    "error",
+                    "-".to_string(),
+                    "-".to_string(),
+                    error.clone(),
+                    Vec::new(),
+                ),
+            };
+
+            ReportRow {
+                status,
+                file1: file1.to_string(),
+                file2: file2.to_string(),
+                similarity_pct: similarity * 100.0,
+                sim_class,
+                kind,
+                common,
+                only1,
+                only2,
+                diff_lines,
+            }
+        })
+        .collect()
+}
+
+/// Render a self-contained HTML report from comparison results.
+pub fn export_html(
+    results: &[ComparisonResult],
+    summary: &ComparisonSummary,
+    output_path: &Path,
+) -> Result<()> {
+    let mut context = tera::Context::new();
+    context.insert("summary", summary);
+    context.insert("rows", &build_report_rows(results));
+
+    let html = Tera::one_off(HTML_TEMPLATE, &context, true)
+        .context("Failed to render HTML report template")?;
+
+    fs::write(output_path, html)
+        .with_context(|| format!("Failed to write HTML report to {}", output_path.display()))?;
+
+    Ok(())
+}
+
 /// Write patch files for text comparison results
 pub fn write_patches(results: &[ComparisonResult], output_dir: &Path) -> Result<()> {
     let patches_dir = output_dir.join("patches");
@@ -177,6 +573,27 @@ pub fn write_mismatch_artifacts(results: &[ComparisonResult], output_dir: &Path)
     Ok(())
 }
 
+/// Write structural-difference artifacts for JSON comparison results
+pub fn write_json_artifacts(results: &[ComparisonResult], output_dir: &Path) -> Result<()> {
+    let json_dir = output_dir.join("json");
+    fs::create_dir_all(&json_dir)?;
+
+    for result in results {
+        if let ComparisonResult::Json(r) = result {
+            if !r.identical {
+                let filename = sanitize_filename(&r.linked_id) + ".json";
+                let path = json_dir.join(&filename);
+
+                let json = serde_json::to_string_pretty(r)?;
+                fs::write(&path, json)
+                    .with_context(|| format!("Failed to write JSON artifact {}", path.display()))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Sanitize a string for use as a filename
 fn sanitize_filename(s: &str) -> String {
     s.chars()
@@ -230,25 +647,39 @@ pub fn calculate_summary(results: &[ComparisonResult], total1: usize, total2: us
     }
 }
 
-/// Export all artifacts (JSONL, CSV, patches, mismatches)
+/// Export all artifacts (JSONL, CSV, HTML, patches, mismatches)
 pub fn export_all(
     results: &[ComparisonResult],
+    summary: &ComparisonSummary,
     jsonl_path: Option<&Path>,
+    json_format: JsonFormat,
     csv_path: Option<&Path>,
+    html_path: Option<&Path>,
+    out_json_path: Option<&Path>,
+    out_json_compact: bool,
     output_dir: Option<&Path>,
 ) -> Result<()> {
     if let Some(path) = jsonl_path {
-        export_jsonl(results, path)?;
+        export_json(results, path, json_format)?;
     }
 
     if let Some(path) = csv_path {
         export_csv(results, path)?;
     }
 
+    if let Some(path) = out_json_path {
+        export_combined_json(results, summary, path, out_json_compact)?;
+    }
+
+    if let Some(path) = html_path {
+        export_html(results, summary, path)?;
+    }
+
     if let Some(dir) = output_dir {
         fs::create_dir_all(dir)?;
         write_patches(results, dir)?;
         write_mismatch_artifacts(results, dir)?;
+        write_json_artifacts(results, dir)?;
     }
 
     Ok(())
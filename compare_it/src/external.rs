@@ -0,0 +1,117 @@
+//! External comparison command support
+//!
+//! Delegates comparison of file types the crate cannot parse natively (PDFs,
+//! compiled artifacts, domain formats) to a user-configured program. The two
+//! file paths are substituted into the argument template — or, when no
+//! `{file1}`/`{file2}` placeholder is present, prepended as the first two
+//! arguments. Exit code 0 is treated as identical and any non-zero code as
+//! different, with stdout/stderr captured into the detailed-diff field so the
+//! result still flows through the JSONL/CSV/patch exporters.
+
+use crate::types::{ComparisonResult, FileEntry, TextComparisonResult};
+use std::process::Command;
+
+/// Configuration for delegating a comparison to an external program.
+#[derive(Debug, Clone)]
+pub struct ExternalComparator {
+    /// Executable to invoke.
+    pub program: String,
+    /// Extra argument template; supports `{file1}`/`{file2}` placeholders.
+    pub args: Vec<String>,
+    /// Lowercase extensions this comparator handles (empty = all files).
+    pub extensions: Vec<String>,
+}
+
+impl ExternalComparator {
+    /// Whether this comparator should handle the given file.
+    pub fn matches(&self, entry: &FileEntry) -> bool {
+        self.extensions.is_empty() || self.extensions.contains(&entry.extension)
+    }
+
+    /// Resolve the argument list, substituting file paths into the template.
+    fn resolve_args(&self, file1: &str, file2: &str) -> Vec<String> {
+        let has_placeholder = self
+            .args
+            .iter()
+            .any(|a| a.contains("{file1}") || a.contains("{file2}"));
+
+        if has_placeholder {
+            self.args
+                .iter()
+                .map(|a| a.replace("{file1}", file1).replace("{file2}", file2))
+                .collect()
+        } else {
+            let mut resolved = vec![file1.to_string(), file2.to_string()];
+            resolved.extend(self.args.iter().cloned());
+            resolved
+        }
+    }
+
+    /// Run the external program over a pair and build a result.
+    ///
+    /// Launch failures surface as [`ComparisonResult::Error`]; otherwise the
+    /// exit status decides identity and the captured output (truncated to
+    /// `max_diff_bytes`) becomes the detailed diff.
+    pub fn run(
+        &self,
+        file1: &FileEntry,
+        file2: &FileEntry,
+        max_diff_bytes: usize,
+    ) -> ComparisonResult {
+        let file1_path = file1.path.display().to_string();
+        let file2_path = file2.path.display().to_string();
+        let args = self.resolve_args(&file1_path, &file2_path);
+
+        let output = match Command::new(&self.program).args(&args).output() {
+            Ok(output) => output,
+            Err(e) => {
+                return ComparisonResult::Error {
+                    file1_path,
+                    file2_path,
+                    error: format!("Failed to launch '{}': {}", self.program, e),
+                };
+            }
+        };
+
+        let identical = output.status.success();
+
+        let mut detailed = String::from_utf8_lossy(&output.stdout).into_owned();
+        if !output.stderr.is_empty() {
+            detailed.push_str(&String::from_utf8_lossy(&output.stderr));
+        }
+        let diff_truncated = detailed.len() > max_diff_bytes;
+        if diff_truncated {
+            // Walk back to a UTF-8 char boundary so multibyte output doesn't
+            // panic `String::truncate`.
+            let mut cut = max_diff_bytes;
+            while cut > 0 && !detailed.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            detailed.truncate(cut);
+        }
+
+        let linked_id = format!(
+            "{}:{}",
+            &file1.content_hash[..16.min(file1.content_hash.len())],
+            &file2.content_hash[..16.min(file2.content_hash.len())]
+        );
+
+        ComparisonResult::Text(TextComparisonResult {
+            linked_id,
+            file1_path,
+            file2_path,
+            // The targets this feature handles (PDFs, binaries, images) were
+            // never line-parsed, so don't imply a line-level comparison.
+            file1_line_count: 0,
+            file2_line_count: 0,
+            common_lines: 0,
+            only_in_file1: 0,
+            only_in_file2: 0,
+            similarity_score: if identical { 1.0 } else { 0.0 },
+            different_positions: String::new(),
+            detailed_diff: detailed,
+            diff_truncated,
+            identical,
+        })
+    }
+}
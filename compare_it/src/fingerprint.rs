@@ -5,21 +5,110 @@
 //! - Simhash fingerprints for text similarity estimation
 //! - Schema signatures for structured file matching
 
-use crate::types::{FileEntry, FileType, NormalizationOptions};
+use crate::types::{FileEntry, FileType, HashAlgorithm, HashStrategy, NormalizationOptions};
 use anyhow::Result;
 use rayon::prelude::*;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
 use std::fs;
 use std::hash::{Hash, Hasher};
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Size of the prefix block read for a partial hash.
+const PARTIAL_BLOCK: usize = 4096;
+
+impl HashAlgorithm {
+    /// Hash a byte slice and return the hex digest, dispatching on algorithm.
+    pub fn hash_bytes(&self, data: &[u8]) -> String {
+        match self {
+            HashAlgorithm::Blake3 => blake3::hash(data).to_hex().to_string(),
+            HashAlgorithm::Crc32 => format!("{:08x}", crc32fast::hash(data)),
+            HashAlgorithm::Xxh3 => format!("{:016x}", xxhash_rust::xxh3::xxh3_64(data)),
+            HashAlgorithm::Sha256 => {
+                use sha2::{Digest, Sha256};
+                let mut hasher = Sha256::new();
+                hasher.update(data);
+                format!("{:x}", hasher.finalize())
+            }
+        }
+    }
+}
+
+/// Which portion of a file a hash covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashMode {
+    /// Hash only the first [`PARTIAL_BLOCK`] bytes (cheap pre-filter)
+    Partial,
+    /// Hash the entire file contents
+    Full,
+}
+
+/// Hash a file according to `mode` using `algorithm`, returning the hex digest.
+pub fn hash_file(path: &Path, mode: HashMode, algorithm: HashAlgorithm) -> Result<String> {
+    match mode {
+        HashMode::Partial => {
+            let file = fs::File::open(path)?;
+            let mut reader = BufReader::new(file);
+            let mut buf = vec![0u8; PARTIAL_BLOCK];
+            let n = reader.read(&mut buf)?;
+            buf.truncate(n);
+            Ok(algorithm.hash_bytes(&buf))
+        }
+        HashMode::Full => {
+            let content = fs::read(path)?;
+            Ok(algorithm.hash_bytes(&content))
+        }
+    }
+}
+
+/// Decide whether two files are byte-identical.
+///
+/// Under [`HashStrategy::TwoStage`], files of differing size are rejected for
+/// free, then a cheap prefix hash is compared before paying for a full content
+/// hash — so obviously-different large binaries never get fully read. Under
+/// [`HashStrategy::Full`] the full content hashes are compared directly,
+/// computing them on the fly when they were not materialized during indexing.
+pub fn files_match(f1: &FileEntry, f2: &FileEntry, strategy: HashStrategy) -> Result<bool> {
+    if f1.size != f2.size {
+        return Ok(false);
+    }
+
+    match strategy {
+        HashStrategy::TwoStage => {
+            let p1 = hash_file(&f1.path, HashMode::Partial, f1.hash_algorithm)?;
+            let p2 = hash_file(&f2.path, HashMode::Partial, f2.hash_algorithm)?;
+            if p1 != p2 {
+                return Ok(false);
+            }
+            let full1 = hash_file(&f1.path, HashMode::Full, f1.hash_algorithm)?;
+            let full2 = hash_file(&f2.path, HashMode::Full, f2.hash_algorithm)?;
+            Ok(full1 == full2)
+        }
+        HashStrategy::Full => {
+            let full1 = if f1.content_hash.is_empty() {
+                hash_file(&f1.path, HashMode::Full, f1.hash_algorithm)?
+            } else {
+                f1.content_hash.clone()
+            };
+            let full2 = if f2.content_hash.is_empty() {
+                hash_file(&f2.path, HashMode::Full, f2.hash_algorithm)?
+            } else {
+                f2.content_hash.clone()
+            };
+            Ok(full1 == full2)
+        }
+    }
+}
 
 /// Compute all fingerprints for a set of file entries
 pub fn compute_fingerprints(
     entries: &mut [FileEntry],
     normalization: &NormalizationOptions,
+    algorithm: HashAlgorithm,
 ) {
     entries.par_iter_mut().for_each(|entry| {
-        if let Err(e) = compute_fingerprint_for_entry(entry, normalization) {
+        if let Err(e) = compute_fingerprint_for_entry(entry, normalization, algorithm) {
             eprintln!(
                 "Warning: Failed to fingerprint {}: {}",
                 entry.path.display(),
@@ -29,25 +118,62 @@ pub fn compute_fingerprints(
     });
 }
 
-/// Compute fingerprints for a single file entry
+/// Compute fingerprints, reusing a persistent cache where files are unchanged.
+///
+/// For each entry, a size+mtime cache hit copies the stored fingerprints and
+/// skips the file read; misses compute fresh fingerprints and update the cache
+/// so a subsequent run sees them.
+pub fn compute_fingerprints_cached(
+    entries: &mut [FileEntry],
+    normalization: &NormalizationOptions,
+    algorithm: HashAlgorithm,
+    cache: &mut crate::cache::FingerprintCache,
+) {
+    // Reload unchanged entries from the cache in one pass; only the stale
+    // positions below pay for a fresh read.
+    let stale = cache.hydrate_index(entries);
+    for i in stale {
+        if let Err(e) = compute_fingerprint_for_entry(&mut entries[i], normalization, algorithm) {
+            eprintln!(
+                "Warning: Failed to fingerprint {}: {}",
+                entries[i].path.display(),
+                e
+            );
+        }
+        cache.store(&entries[i]);
+    }
+}
+
+/// Compute fingerprints for a single file entry.
+///
+/// Text, CSV/TSV and image files are read in full because their similarity
+/// fingerprints (simhash / perceptual hash) need the whole content, so their
+/// content hash is materialized for free. Binary and unknown files only get a
+/// cheap `partial_hash` over the first [`PARTIAL_BLOCK`] bytes; their full
+/// `content_hash` is left empty and computed lazily by [`files_match`] once a
+/// same-size, same-partial-hash pair actually needs byte-exact confirmation.
+/// A folder of thousands of distinct binaries therefore avoids reading most
+/// of their bytes.
 fn compute_fingerprint_for_entry(
     entry: &mut FileEntry,
     normalization: &NormalizationOptions,
+    algorithm: HashAlgorithm,
 ) -> Result<()> {
-    // Read file content
-    let content = fs::read(&entry.path)?;
-
-    // Compute blake3 hash
-    let hash = blake3::hash(&content);
-    entry.content_hash = hash.to_hex().to_string();
+    entry.hash_algorithm = algorithm;
 
     // Compute type-specific fingerprints
     match entry.file_type {
         FileType::Text => {
+            let content = fs::read(&entry.path)?;
+            entry.content_hash = algorithm.hash_bytes(&content);
+            entry.partial_hash = Some(partial_hash_of(&content, algorithm));
             let text = String::from_utf8_lossy(&content);
             entry.simhash = Some(compute_simhash(&text, normalization));
         }
         FileType::Csv | FileType::Tsv => {
+            let content = fs::read(&entry.path)?;
+            entry.content_hash = algorithm.hash_bytes(&content);
+            entry.partial_hash = Some(partial_hash_of(&content, algorithm));
             // Compute schema signature from columns
             if let Some(ref columns) = entry.columns {
                 entry.schema_signature = Some(compute_schema_signature(columns));
@@ -56,14 +182,66 @@ fn compute_fingerprint_for_entry(
             let text = String::from_utf8_lossy(&content);
             entry.simhash = Some(compute_simhash(&text, normalization));
         }
+        FileType::Image => {
+            let content = fs::read(&entry.path)?;
+            entry.content_hash = algorithm.hash_bytes(&content);
+            entry.partial_hash = Some(partial_hash_of(&content, algorithm));
+            // Perceptual hash decoded from the image bytes, stored in the
+            // simhash slot so similarity matching reuses the Hamming path.
+            match compute_image_hash(&content) {
+                Ok(hash) => entry.simhash = Some(hash),
+                Err(e) => eprintln!(
+                    "Warning: Failed to perceptual-hash {}: {}",
+                    entry.path.display(),
+                    e
+                ),
+            }
+        }
         FileType::Binary | FileType::Unknown => {
-            // No simhash for binary files
+            // Binaries have no similarity fingerprint, so read only the prefix
+            // block for a cheap blocking hash and defer the full hash.
+            entry.partial_hash = Some(hash_file(&entry.path, HashMode::Partial, algorithm)?);
         }
     }
 
     Ok(())
 }
 
+/// Partial hash of already-read content: the first [`PARTIAL_BLOCK`] bytes.
+fn partial_hash_of(content: &[u8], algorithm: HashAlgorithm) -> String {
+    let end = PARTIAL_BLOCK.min(content.len());
+    algorithm.hash_bytes(&content[..end])
+}
+
+/// Compute a 64-bit perceptual hash (dHash) from encoded image bytes.
+///
+/// The image is decoded, reduced to a 9x8 grayscale thumbnail, and each pixel
+/// compared to its right neighbour to yield 64 gradient bits. Visually similar
+/// images produce hashes a small Hamming distance apart, so the existing
+/// simhash comparison path scores them directly.
+pub fn compute_image_hash(bytes: &[u8]) -> Result<u64> {
+    let img = image::load_from_memory(bytes)?;
+    // 9 wide so 8 horizontal comparisons fit per row, 8 rows = 64 bits.
+    let thumb = img
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .to_luma8();
+
+    let mut hash: u64 = 0;
+    let mut bit = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = thumb.get_pixel(x, y)[0];
+            let right = thumb.get_pixel(x + 1, y)[0];
+            if left > right {
+                hash |= 1 << bit;
+            }
+            bit += 1;
+        }
+    }
+
+    Ok(hash)
+}
+
 /// Compute simhash fingerprint for text content
 ///
 /// Simhash is a locality-sensitive hash that produces similar hashes
@@ -162,7 +340,7 @@ fn generate_shingles(lines: &[String], n: usize) -> Vec<String> {
 }
 
 /// Hash a string to u64 using DefaultHasher
-fn hash_string(s: &str) -> u64 {
+pub fn hash_string(s: &str) -> u64 {
     let mut hasher = DefaultHasher::new();
     s.hash(&mut hasher);
     hasher.finish()
@@ -180,6 +358,62 @@ pub fn compute_schema_signature(columns: &[String]) -> String {
     hash.to_hex()[..16].to_string()
 }
 
+/// Normalize a set of column names into a deduplicated set.
+///
+/// Column names are trimmed and (when `ignore_case` is set) lowercased so that
+/// cosmetic header differences don't make two otherwise-identical schemas look
+/// unrelated.
+fn normalize_columns(columns: &[String], opts: &NormalizationOptions) -> HashSet<String> {
+    columns
+        .iter()
+        .map(|c| {
+            let mut s = c.trim().to_string();
+            if opts.ignore_all_ws {
+                s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+            }
+            if opts.ignore_case {
+                s = s.to_lowercase();
+            }
+            s
+        })
+        .collect()
+}
+
+/// Jaccard similarity of two column-name sets: `|A∩B| / |A∪B|`.
+///
+/// Unlike [`compute_schema_signature`], which only matches byte-identical
+/// header sets, this degrades gracefully: a single renamed or added column
+/// lowers the score rather than dropping it to zero, so evolving schema
+/// versions can be clustered together.
+pub fn schema_similarity(cols_a: &[String], cols_b: &[String], opts: &NormalizationOptions) -> f64 {
+    let a = normalize_columns(cols_a, opts);
+    let b = normalize_columns(cols_b, opts);
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let intersection = a.intersection(&b).count();
+    let union = a.union(&b).count();
+    if union == 0 {
+        0.0
+    } else {
+        intersection as f64 / union as f64
+    }
+}
+
+/// Containment of schema A within schema B: `|A∩B| / |A|`.
+///
+/// A score of `1.0` means every column of A also appears in B (A is a subset),
+/// which detects "same table, extra column" superset/subset relationships.
+pub fn schema_containment(cols_a: &[String], cols_b: &[String], opts: &NormalizationOptions) -> f64 {
+    let a = normalize_columns(cols_a, opts);
+    let b = normalize_columns(cols_b, opts);
+    if a.is_empty() {
+        return 1.0;
+    }
+    let intersection = a.intersection(&b).count();
+    intersection as f64 / a.len() as f64
+}
+
 /// Compute Hamming distance between two simhashes
 ///
 /// Returns the number of differing bits (0-64)
@@ -248,6 +482,28 @@ mod tests {
         assert!(simhash_similarity(0, u64::MAX) < 0.1);
     }
 
+    #[test]
+    fn test_partial_hash_of_covers_prefix_only() {
+        // Files sharing their first block share a partial hash regardless of
+        // what follows, so the prefix block is an effective cheap pre-filter.
+        let mut a = vec![b'x'; PARTIAL_BLOCK];
+        let mut b = a.clone();
+        a.extend_from_slice(b"tail-a");
+        b.extend_from_slice(b"tail-b");
+        assert_eq!(
+            partial_hash_of(&a, HashAlgorithm::Blake3),
+            partial_hash_of(&b, HashAlgorithm::Blake3)
+        );
+
+        // A difference inside the first block changes the partial hash.
+        let mut c = vec![b'x'; PARTIAL_BLOCK];
+        c[0] = b'y';
+        assert_ne!(
+            partial_hash_of(&a, HashAlgorithm::Blake3),
+            partial_hash_of(&c, HashAlgorithm::Blake3)
+        );
+    }
+
     #[test]
     fn test_schema_signature() {
         let cols1 = vec!["a".to_string(), "b".to_string(), "c".to_string()];
@@ -258,4 +514,24 @@ mod tests {
             compute_schema_signature(&cols2)
         );
     }
+
+    #[test]
+    fn test_schema_similarity() {
+        let opts = NormalizationOptions::default();
+        let a = vec!["id".to_string(), "name".to_string(), "value".to_string()];
+        let b = vec!["id".to_string(), "name".to_string(), "value".to_string()];
+        assert_eq!(schema_similarity(&a, &b, &opts), 1.0);
+
+        // One extra column: 3 shared out of 4 total.
+        let c = vec![
+            "id".to_string(),
+            "name".to_string(),
+            "value".to_string(),
+            "extra".to_string(),
+        ];
+        assert!((schema_similarity(&a, &c, &opts) - 0.75).abs() < 1e-9);
+        // A is fully contained in C.
+        assert_eq!(schema_containment(&a, &c, &opts), 1.0);
+        assert!((schema_containment(&c, &a, &opts) - 0.75).abs() < 1e-9);
+    }
 }
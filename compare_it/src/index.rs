@@ -5,33 +5,78 @@
 
 use crate::types::{FileEntry, FileType};
 use anyhow::{Context, Result};
+use ignore::overrides::OverrideBuilder;
+use ignore::{WalkBuilder, WalkState};
 use rayon::prelude::*;
 use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::sync::Mutex;
+
+/// Options controlling directory traversal during indexing.
+///
+/// The defaults match a focused comparison: hidden entries are skipped and
+/// `.gitignore`/`.ignore` files encountered while descending are honored, so
+/// build artifacts and VCS metadata never reach candidate generation.
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// Include hidden (dotfile) entries
+    pub hidden: bool,
+    /// Disable ignore-file handling (`.gitignore`/`.ignore`/global/excludes)
+    pub no_ignore: bool,
+    /// Additional glob patterns to exclude
+    pub exclude: Vec<String>,
+}
 
 /// Index files from a path (file or directory)
-pub fn index_path(path: &Path) -> Result<Vec<FileEntry>> {
+pub fn index_path(path: &Path, options: &WalkOptions) -> Result<Vec<FileEntry>> {
     if path.is_file() {
         let entry = index_single_file(path)?;
         Ok(vec![entry])
     } else if path.is_dir() {
-        index_directory(path)
+        index_directory(path, options)
     } else {
         anyhow::bail!("Path does not exist or is not accessible: {}", path.display());
     }
 }
 
-/// Index all files in a directory recursively
-pub fn index_directory(dir: &Path) -> Result<Vec<FileEntry>> {
-    let paths: Vec<PathBuf> = WalkDir::new(dir)
+/// Index all files in a directory recursively using the `ignore` crate's
+/// parallel walker, honoring hidden/ignore/exclude settings.
+pub fn index_directory(dir: &Path, options: &WalkOptions) -> Result<Vec<FileEntry>> {
+    let mut builder = WalkBuilder::new(dir);
+    builder
         .follow_links(false)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .map(|e| e.path().to_path_buf())
-        .collect();
+        .hidden(!options.hidden)
+        .parents(!options.no_ignore)
+        .ignore(!options.no_ignore)
+        .git_ignore(!options.no_ignore)
+        .git_global(!options.no_ignore)
+        .git_exclude(!options.no_ignore);
+
+    // Extra `--exclude` globs are added as override ignore rules (the leading
+    // `!` marks an override glob as an exclusion rather than a whitelist).
+    if !options.exclude.is_empty() {
+        let mut overrides = OverrideBuilder::new(dir);
+        for pattern in &options.exclude {
+            overrides
+                .add(&format!("!{}", pattern))
+                .with_context(|| format!("Invalid --exclude glob: {}", pattern))?;
+        }
+        builder.overrides(overrides.build().context("Failed to build exclude globs")?);
+    }
+
+    let paths = Mutex::new(Vec::new());
+    builder.build_parallel().run(|| {
+        Box::new(|result| {
+            if let Ok(entry) = result {
+                if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
+                    paths.lock().unwrap().push(entry.path().to_path_buf());
+                }
+            }
+            WalkState::Continue
+        })
+    });
+    let paths = paths.into_inner().unwrap();
 
     // Process files in parallel
     let mut entries: Vec<FileEntry> = paths
@@ -51,6 +96,11 @@ pub fn index_single_file(path: &Path) -> Result<FileEntry> {
         .with_context(|| format!("Failed to read metadata for {}", path.display()))?;
 
     let size = metadata.len();
+    let modified_time = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
     let extension = path
         .extension()
         .and_then(|e| e.to_str())
@@ -66,9 +116,12 @@ pub fn index_single_file(path: &Path) -> Result<FileEntry> {
     Ok(FileEntry {
         path: path.to_path_buf(),
         size,
+        modified_time,
         file_type,
         extension,
         content_hash,
+        hash_algorithm: crate::types::HashAlgorithm::default(),
+        partial_hash: None,
         simhash: None,
         schema_signature: None,
         line_count,
@@ -78,6 +131,12 @@ pub fn index_single_file(path: &Path) -> Result<FileEntry> {
 
 /// Detect file type by examining content and extension
 fn detect_file_type(path: &Path, extension: &str) -> Result<(FileType, usize, Option<Vec<String>>)> {
+    // Images are decoded, not read line-by-line; classify them by extension up
+    // front so perceptual hashing happens in the fingerprint stage.
+    if matches!(extension, "png" | "jpg" | "jpeg" | "bmp" | "webp") {
+        return Ok((FileType::Image, 0, None));
+    }
+
     // Check extension first for structured files
     let is_csv_ext = extension == "csv";
     let is_tsv_ext = extension == "tsv" || extension == "tab";
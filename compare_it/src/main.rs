@@ -6,33 +6,43 @@
 //! - All-vs-all folder matching with fingerprint-based candidate pruning
 //! - Multiple export formats (JSONL, CSV, HTML)
 
+mod bktree;
+mod cache;
+mod cell_diff;
+mod compare_image;
+mod compare_json;
 mod compare_structured;
 mod compare_text;
 mod export;
+mod external;
 mod fingerprint;
 mod index;
 mod match_files;
 mod report;
+mod structural;
 mod types;
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Cell, Color, Table};
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
 use std::path::PathBuf;
 
+use crate::bktree::cluster;
+use crate::compare_image::compare_image_files;
+use crate::compare_json::compare_json_files;
 use crate::compare_structured::compare_structured_files;
 use crate::compare_text::compare_text_files;
 use crate::export::{calculate_summary, export_all};
-use crate::fingerprint::compute_fingerprints;
+use crate::fingerprint::{compute_fingerprints, compute_fingerprints_cached};
 use crate::index::index_path;
 use crate::match_files::generate_candidates;
 use crate::report::{generate_html_report, load_results_from_jsonl};
 use crate::types::{
     CandidatePair, CompareConfig, CompareMode, ComparisonResult, FileEntry, FileType,
-    NormalizationOptions, PairingStrategy, SimilarityAlgorithm,
+    NormalizationOptions, PairingStrategy, Rule, SimilarityAlgorithm,
 };
 
 /// CompareIt - High-performance file comparison tool
@@ -44,6 +54,15 @@ struct Cli {
     command: Commands,
 }
 
+/// JSON output layout selectable on the command line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum JsonFormatArg {
+    /// One JSON object per line.
+    Jsonl,
+    /// A single JSON array document.
+    Array,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Compare files or folders
@@ -66,6 +85,10 @@ enum Commands {
         #[arg(long, default_value = "3")]
         topk: usize,
 
+        /// Hamming radius for the simhash BK-tree candidate search
+        #[arg(long, default_value = "12")]
+        simhash_radius: u32,
+
         /// Maximum number of pairs to compare
         #[arg(long)]
         max_pairs: Option<usize>,
@@ -78,10 +101,46 @@ enum Commands {
         #[arg(long, default_value = "0.0001")]
         numeric_tol: f64,
 
+        /// Levenshtein radius for fuzzy key linkage in structured comparison
+        #[arg(long)]
+        fuzzy_key_radius: Option<u32>,
+
+        /// Source encoding for the CSV/TSV parser
+        #[arg(long, default_value = "auto")]
+        encoding: types::FileEncoding,
+
+        /// Max Hamming distance for image near-duplicate matching
+        #[arg(long, default_value = "10")]
+        image_threshold: u32,
+
+        /// Match JSON array elements order-insensitively
+        #[arg(long)]
+        json_unordered: bool,
+
         /// Similarity algorithm (diff, char-jaro)
         #[arg(long, default_value = "diff")]
         similarity: SimilarityAlgorithm,
 
+        /// Content-hash algorithm (blake3, crc32, xxh3, sha256)
+        #[arg(long, default_value = "blake3")]
+        hash_type: types::HashAlgorithm,
+
+        /// Binary hash strategy (full, two-stage)
+        #[arg(long, default_value = "full")]
+        hash_strategy: types::HashStrategy,
+
+        /// External comparison program for unsupported formats
+        #[arg(long)]
+        external_cmd: Option<String>,
+
+        /// Extra args for the external comparator (supports {file1}/{file2})
+        #[arg(long, value_delimiter = ',')]
+        external_args: Vec<String>,
+
+        /// Extensions the external comparator handles (empty = all)
+        #[arg(long, value_delimiter = ',')]
+        external_ext: Vec<String>,
+
         /// Normalize line endings
         #[arg(long)]
         ignore_eol: bool,
@@ -106,23 +165,93 @@ enum Commands {
         #[arg(long, default_value = "1048576")]
         max_diff_bytes: usize,
 
+        /// Highlight intra-line changes at the character level in diffs
+        #[arg(long)]
+        inline_diff: bool,
+
         /// Output JSONL file path
         #[arg(long)]
         out_jsonl: Option<PathBuf>,
 
+        /// Layout for the JSON output file (jsonl or array)
+        #[arg(long, value_enum, default_value_t = JsonFormatArg::Jsonl)]
+        json_format: JsonFormatArg,
+
+        /// Pretty-print the JSON array output (only with --json-format array)
+        #[arg(long)]
+        json_pretty: bool,
+
         /// Output CSV file path
         #[arg(long)]
         out_csv: Option<PathBuf>,
 
+        /// Output self-contained HTML report path
+        #[arg(long)]
+        out_html: Option<PathBuf>,
+
         /// Output directory for patches and artifacts
         #[arg(long)]
         out_dir: Option<PathBuf>,
 
+        /// Combined `{ summary, results }` JSON report path (for CI)
+        #[arg(long)]
+        out_json: Option<PathBuf>,
+
+        /// Drop pretty-printing from the combined --out-json report
+        #[arg(long)]
+        compact: bool,
+
+        /// Exit nonzero when any pair differs or errors
+        #[arg(long)]
+        fail_on_diff: bool,
+
+        /// Path to a fingerprint cache file (reused across runs)
+        #[arg(long)]
+        cache: Option<PathBuf>,
+
+        /// YAML file of per-pattern comparison rules
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        /// Include hidden (dotfile) entries while indexing
+        #[arg(long)]
+        hidden: bool,
+
+        /// Disable .gitignore/.ignore handling while indexing
+        #[arg(long)]
+        no_ignore: bool,
+
+        /// Glob of paths to exclude from indexing (repeatable)
+        #[arg(long)]
+        exclude: Vec<String>,
+
         /// Verbose output
         #[arg(short, long)]
         verbose: bool,
     },
 
+    /// Group near-duplicate files within a folder by simhash
+    Cluster {
+        /// File or folder path to scan
+        path: PathBuf,
+
+        /// Hamming radius for grouping near-duplicate simhashes
+        #[arg(long, default_value = "12")]
+        simhash_radius: u32,
+
+        /// Include hidden (dotfile) entries while indexing
+        #[arg(long)]
+        hidden: bool,
+
+        /// Disable .gitignore/.ignore handling while indexing
+        #[arg(long)]
+        no_ignore: bool,
+
+        /// Glob of paths to exclude from indexing (repeatable)
+        #[arg(long)]
+        exclude: Vec<String>,
+    },
+
     /// Generate HTML report from comparison results
     Report {
         /// Input JSONL file with comparison results
@@ -149,28 +278,66 @@ fn main() -> Result<()> {
             mode,
             pairing,
             topk,
+            simhash_radius,
             max_pairs,
             key,
             numeric_tol,
+            fuzzy_key_radius,
+            encoding,
+            image_threshold,
+            json_unordered,
             similarity,
+            hash_type,
+            hash_strategy,
+            external_cmd,
+            external_args,
+            external_ext,
             ignore_eol,
             ignore_trailing_ws,
             ignore_all_ws,
             ignore_case,
             skip_empty_lines,
             max_diff_bytes,
+            inline_diff,
             out_jsonl,
+            json_format,
+            json_pretty,
             out_csv,
+            out_html,
             out_dir,
+            out_json,
+            compact,
+            fail_on_diff,
+            cache,
+            config: config_path,
+            hidden,
+            no_ignore,
+            exclude,
             verbose,
         } => {
+            let rules = match config_path {
+                Some(ref path) => load_rules(path)?,
+                None => Vec::new(),
+            };
+            let walk = index::WalkOptions {
+                hidden,
+                no_ignore,
+                exclude,
+            };
             let config = CompareConfig {
                 mode,
                 pairing,
                 top_k: topk,
+                simhash_radius,
                 max_pairs,
                 key_columns: key,
                 numeric_tolerance: numeric_tol,
+                matching_rules: std::collections::HashMap::new(),
+                default_matching_rule: types::MatchingRule::Equality,
+                fuzzy_key_radius,
+                encoding,
+                image_threshold,
+                json_array_order_insensitive: json_unordered,
                 normalization: NormalizationOptions {
                     ignore_eol,
                     ignore_trailing_ws,
@@ -180,15 +347,51 @@ fn main() -> Result<()> {
                 },
                 similarity_algorithm: similarity,
                 max_diff_bytes,
+                inline_char_diff: inline_diff,
+                hash_algorithm: hash_type,
+                hash_strategy,
+                external: external_cmd.map(|program| external::ExternalComparator {
+                    program,
+                    args: external_args,
+                    extensions: external_ext.iter().map(|e| e.to_lowercase()).collect(),
+                }),
+                json_format: match json_format {
+                    JsonFormatArg::Jsonl => export::JsonFormat::Jsonl,
+                    JsonFormatArg::Array => export::JsonFormat::Array {
+                        pretty: json_pretty,
+                    },
+                },
                 output_jsonl: out_jsonl,
                 output_csv: out_csv,
+                output_html: out_html,
                 output_dir: out_dir,
+                output_json: out_json,
+                output_json_compact: compact,
+                fail_on_diff,
+                cache_path: cache,
+                walk,
+                rules,
                 verbose,
             };
 
             run_compare(&path1, &path2, &config)?;
         }
 
+        Commands::Cluster {
+            path,
+            simhash_radius,
+            hidden,
+            no_ignore,
+            exclude,
+        } => {
+            let walk = index::WalkOptions {
+                hidden,
+                no_ignore,
+                exclude,
+            };
+            run_cluster(&path, simhash_radius, &walk)?;
+        }
+
         Commands::Report {
             input,
             html,
@@ -208,8 +411,8 @@ fn run_compare(path1: &PathBuf, path2: &PathBuf, config: &CompareConfig) -> Resu
 
     // Stage 1: Index files
     println!("\n{} Indexing files...", style("[1/4]").bold());
-    let mut files1 = index_path(path1).context("Failed to index path1")?;
-    let mut files2 = index_path(path2).context("Failed to index path2")?;
+    let mut files1 = index_path(path1, &config.walk).context("Failed to index path1")?;
+    let mut files2 = index_path(path2, &config.walk).context("Failed to index path2")?;
 
     println!(
         "  Found {} files in path1, {} files in path2",
@@ -220,12 +423,37 @@ fn run_compare(path1: &PathBuf, path2: &PathBuf, config: &CompareConfig) -> Resu
     // Stage 2: Compute fingerprints
     println!("\n{} Computing fingerprints...", style("[2/4]").bold());
     let pb = create_progress_bar((files1.len() + files2.len()) as u64);
-    
-    compute_fingerprints(&mut files1, &config.normalization);
-    pb.inc(files1.len() as u64);
-    
-    compute_fingerprints(&mut files2, &config.normalization);
-    pb.finish_with_message("Done");
+
+    if let Some(ref cache_path) = config.cache_path {
+        let signature = cache::cache_signature(&config.normalization, config.hash_algorithm);
+        let mut cache = cache::FingerprintCache::load(cache_path, signature);
+
+        compute_fingerprints_cached(
+            &mut files1,
+            &config.normalization,
+            config.hash_algorithm,
+            &mut cache,
+        );
+        pb.inc(files1.len() as u64);
+
+        compute_fingerprints_cached(
+            &mut files2,
+            &config.normalization,
+            config.hash_algorithm,
+            &mut cache,
+        );
+        pb.finish_with_message("Done");
+
+        if let Err(e) = cache.save(cache_path) {
+            eprintln!("Warning: failed to save fingerprint cache: {e}");
+        }
+    } else {
+        compute_fingerprints(&mut files1, &config.normalization, config.hash_algorithm);
+        pb.inc(files1.len() as u64);
+
+        compute_fingerprints(&mut files2, &config.normalization, config.hash_algorithm);
+        pb.finish_with_message("Done");
+    }
 
     // Stage 3: Generate candidate pairs
     println!("\n{} Generating candidates...", style("[3/4]").bold());
@@ -267,12 +495,22 @@ fn run_compare(path1: &PathBuf, path2: &PathBuf, config: &CompareConfig) -> Resu
     }
 
     // Export results
-    if config.output_jsonl.is_some() || config.output_csv.is_some() || config.output_dir.is_some() {
+    if config.output_jsonl.is_some()
+        || config.output_csv.is_some()
+        || config.output_html.is_some()
+        || config.output_json.is_some()
+        || config.output_dir.is_some()
+    {
         println!("\n{}", style("Exporting results...").dim());
         export_all(
             &results,
+            &summary,
             config.output_jsonl.as_deref(),
+            config.json_format,
             config.output_csv.as_deref(),
+            config.output_html.as_deref(),
+            config.output_json.as_deref(),
+            config.output_json_compact,
             config.output_dir.as_deref(),
         )?;
 
@@ -282,11 +520,73 @@ fn run_compare(path1: &PathBuf, path2: &PathBuf, config: &CompareConfig) -> Resu
         if let Some(ref path) = config.output_csv {
             println!("  CSV: {}", path.display());
         }
+        if let Some(ref path) = config.output_html {
+            println!("  HTML: {}", path.display());
+        }
+        if let Some(ref path) = config.output_json {
+            println!("  JSON: {}", path.display());
+        }
         if let Some(ref path) = config.output_dir {
             println!("  Artifacts: {}/", path.display());
         }
     }
 
+    println!("\n{}", style("✓ Complete").green().bold());
+
+    // Exit-code contract: when requested, a differing or errored run fails the
+    // process so CI can gate on it.
+    if config.fail_on_diff && (summary.different_pairs > 0 || summary.error_pairs > 0) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Run the cluster command: group near-duplicate files in a folder.
+///
+/// Files are indexed and fingerprinted exactly as in `compare`, then the
+/// simhash BK-tree greedy sweep groups each seed entry with the unassigned
+/// entries within `simhash_radius` bits of it (not transitively through other
+/// members). Only groups with more than one member are reported,
+/// since a singleton is nobody's near-duplicate.
+fn run_cluster(path: &PathBuf, simhash_radius: u32, walk: &index::WalkOptions) -> Result<()> {
+    println!("{}", style("CompareIt Cluster").cyan().bold());
+    println!("{}", style("═".repeat(60)).dim());
+
+    println!("\n{} Indexing files...", style("[1/3]").bold());
+    let mut files = index_path(path, walk).context("Failed to index path")?;
+    println!("  Found {} files", style(files.len()).green());
+
+    println!("\n{} Computing fingerprints...", style("[2/3]").bold());
+    compute_fingerprints(
+        &mut files,
+        &NormalizationOptions::default(),
+        types::HashAlgorithm::Blake3,
+    );
+
+    println!("\n{} Clustering near-duplicates...", style("[3/3]").bold());
+    let clusters: Vec<Vec<usize>> = cluster(&files, simhash_radius)
+        .into_iter()
+        .filter(|group| group.len() > 1)
+        .collect();
+
+    if clusters.is_empty() {
+        println!("\n{}", style("No near-duplicate groups found").yellow());
+        return Ok(());
+    }
+
+    println!(
+        "\n{}",
+        style(format!("Found {} near-duplicate group(s)", clusters.len())).cyan().bold()
+    );
+    println!("{}", style("─".repeat(60)).dim());
+    for (i, group) in clusters.iter().enumerate() {
+        println!("\n{}", style(format!("Group {}", i + 1)).bold());
+        for &idx in group {
+            println!("  {}", files[idx].path.display());
+        }
+    }
+
     println!("\n{}", style("✓ Complete").green().bold());
     Ok(())
 }
@@ -313,6 +613,58 @@ fn run_report(input: &PathBuf, html: &PathBuf, artifacts: Option<&std::path::Pat
     Ok(())
 }
 
+/// Load the per-pattern rule set from a YAML config file.
+fn load_rules(path: &std::path::Path) -> Result<Vec<Rule>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let rules: Vec<Rule> = serde_yaml::from_str(&text)
+        .with_context(|| format!("Failed to parse rules from {}", path.display()))?;
+    Ok(rules)
+}
+
+/// Merge the first rule whose glob matches `file1` over the global config.
+///
+/// Returns the base config unchanged when no rules are configured or none
+/// match, so the common single-config case stays allocation-free.
+fn resolve_config<'a>(
+    pair: &CandidatePair,
+    config: &'a CompareConfig,
+) -> std::borrow::Cow<'a, CompareConfig> {
+    if config.rules.is_empty() {
+        return std::borrow::Cow::Borrowed(config);
+    }
+
+    let path = &pair.file1.path;
+    let matched = config.rules.iter().find(|r| {
+        globset::Glob::new(&r.pattern)
+            .map(|g| g.compile_matcher().is_match(path))
+            .unwrap_or(false)
+    });
+
+    match matched {
+        None => std::borrow::Cow::Borrowed(config),
+        Some(rule) => {
+            let mut merged = config.clone();
+            if let Some(mode) = rule.mode {
+                merged.mode = mode;
+            }
+            if let Some(ref key_columns) = rule.key_columns {
+                merged.key_columns = key_columns.clone();
+            }
+            if let Some(tolerance) = rule.numeric_tolerance {
+                merged.numeric_tolerance = tolerance;
+            }
+            if let Some(ref normalization) = rule.normalization {
+                merged.normalization = normalization.clone();
+            }
+            if let Some(algorithm) = rule.similarity_algorithm {
+                merged.similarity_algorithm = algorithm;
+            }
+            std::borrow::Cow::Owned(merged)
+        }
+    }
+}
+
 /// Compare a single candidate pair
 fn compare_pair(pair: &CandidatePair, config: &CompareConfig) -> ComparisonResult {
     // Quick check for identical files
@@ -320,11 +672,64 @@ fn compare_pair(pair: &CandidatePair, config: &CompareConfig) -> ComparisonResul
         return create_identical_result(&pair.file1, &pair.file2);
     }
 
+    // Apply any pattern-scoped rule overrides before dispatching.
+    let config = resolve_config(pair, config);
+    let config = config.as_ref();
+
+    // Force external delegation when the mode asks for it, surfacing a missing
+    // `--external-cmd` as an error rather than silently falling back.
+    if config.mode == CompareMode::External {
+        return match config.external {
+            Some(ref external) => external.run(&pair.file1, &pair.file2, config.max_diff_bytes),
+            None => ComparisonResult::Error {
+                file1_path: pair.file1.path.display().to_string(),
+                file2_path: pair.file2.path.display().to_string(),
+                error: "mode 'external' requires --external-cmd".to_string(),
+            },
+        };
+    }
+
+    // Delegate to an external comparator when one is configured for this type.
+    if let Some(ref external) = config.external {
+        if external.matches(&pair.file1) {
+            return external.run(&pair.file1, &pair.file2, config.max_diff_bytes);
+        }
+    }
+
+    // Image pairs are scored by perceptual hash, not byte content.
+    if pair.file1.file_type == FileType::Image && pair.file2.file_type == FileType::Image {
+        return ComparisonResult::Image(compare_image_files(&pair.file1, &pair.file2));
+    }
+
+    // Binary pairs never diff as text; decide identity via the hash strategy,
+    // which can reject differing files without a full read.
+    if pair.file1.file_type == FileType::Binary || pair.file2.file_type == FileType::Binary {
+        let identical = fingerprint::files_match(&pair.file1, &pair.file2, config.hash_strategy)
+            .unwrap_or(false);
+        let linked_id = format!(
+            "{}:{}",
+            &pair.file1.content_hash[..16.min(pair.file1.content_hash.len())],
+            &pair.file2.content_hash[..16.min(pair.file2.content_hash.len())]
+        );
+        return ComparisonResult::HashOnly {
+            linked_id,
+            file1_path: pair.file1.path.display().to_string(),
+            file2_path: pair.file2.path.display().to_string(),
+            file1_size: pair.file1.size,
+            file2_size: pair.file2.size,
+            identical,
+        };
+    }
+
     // Determine comparison mode
     let mode = match config.mode {
         CompareMode::Auto => auto_detect_mode(&pair.file1, &pair.file2),
         CompareMode::Text => CompareMode::Text,
         CompareMode::Structured => CompareMode::Structured,
+        CompareMode::Json => CompareMode::Json,
+        CompareMode::Image => CompareMode::Image,
+        // External is handled above; treat a stray value as text.
+        CompareMode::External => CompareMode::Text,
     };
 
     match mode {
@@ -348,6 +753,17 @@ fn compare_pair(pair: &CandidatePair, config: &CompareConfig) -> ComparisonResul
                 },
             }
         }
+        CompareMode::Json => {
+            match compare_json_files(&pair.file1, &pair.file2, config) {
+                Ok(result) => ComparisonResult::Json(result),
+                Err(e) => ComparisonResult::Error {
+                    file1_path: pair.file1.path.display().to_string(),
+                    file2_path: pair.file2.path.display().to_string(),
+                    error: e.to_string(),
+                },
+            }
+        }
+        CompareMode::Image => ComparisonResult::Image(compare_image_files(&pair.file1, &pair.file2)),
         CompareMode::Auto => {
             // Fallback to text if auto-detection fails
             match compare_text_files(&pair.file1, &pair.file2, config) {
@@ -364,7 +780,11 @@ fn compare_pair(pair: &CandidatePair, config: &CompareConfig) -> ComparisonResul
 
 /// Auto-detect comparison mode based on file types
 fn auto_detect_mode(file1: &FileEntry, file2: &FileEntry) -> CompareMode {
-    if file1.file_type.is_structured() && file2.file_type.is_structured() {
+    if file1.extension == "json" && file2.extension == "json" {
+        CompareMode::Json
+    } else if file1.file_type == FileType::Image && file2.file_type == FileType::Image {
+        CompareMode::Image
+    } else if file1.file_type.is_structured() && file2.file_type.is_structured() {
         CompareMode::Structured
     } else if file1.file_type == FileType::Binary || file2.file_type == FileType::Binary {
         CompareMode::Text // Will fall through to hash-only
@@ -381,7 +801,17 @@ fn create_identical_result(file1: &FileEntry, file2: &FileEntry) -> ComparisonRe
         &file2.content_hash[..16.min(file2.content_hash.len())]
     );
 
-    if file1.file_type == FileType::Binary || file2.file_type == FileType::Binary {
+    if file1.file_type == FileType::Image && file2.file_type == FileType::Image {
+        ComparisonResult::Image(types::ImageComparisonResult {
+            linked_id,
+            file1_path: file1.path.display().to_string(),
+            file2_path: file2.path.display().to_string(),
+            hamming_distance: 0,
+            verdict: "near-identical".to_string(),
+            similarity_score: 1.0,
+            identical: true,
+        })
+    } else if file1.file_type == FileType::Binary || file2.file_type == FileType::Binary {
         ComparisonResult::HashOnly {
             linked_id,
             file1_path: file1.path.display().to_string(),
@@ -390,6 +820,17 @@ fn create_identical_result(file1: &FileEntry, file2: &FileEntry) -> ComparisonRe
             file2_size: file2.size,
             identical: true,
         }
+    } else if file1.extension == "json" && file2.extension == "json" {
+        ComparisonResult::Json(types::JsonComparisonResult {
+            linked_id,
+            file1_path: file1.path.display().to_string(),
+            file2_path: file2.path.display().to_string(),
+            total_leaf_nodes: 0,
+            matching_leaf_nodes: 0,
+            differences: vec![],
+            similarity_score: 1.0,
+            identical: true,
+        })
     } else if file1.file_type.is_structured() && file2.file_type.is_structured() {
         ComparisonResult::Structured(types::StructuredComparisonResult {
             linked_id,
@@ -406,6 +847,10 @@ fn create_identical_result(file1: &FileEntry, file2: &FileEntry) -> ComparisonRe
             columns_only_in_file1: vec![],
             columns_only_in_file2: vec![],
             common_columns: file1.columns.clone().unwrap_or_default(),
+            schema_similarity: 1.0,
+            schema_containment_file1: 1.0,
+            schema_containment_file2: 1.0,
+            fuzzy_matches: vec![],
             identical: true,
         })
     } else {
@@ -522,6 +967,16 @@ fn display_results_table(results: &[ComparisonResult], verbose: bool) {
                 r.only_in_file1.to_string(),
                 r.only_in_file2.to_string(),
             ),
+            ComparisonResult::Json(r) => (
+                r.matching_leaf_nodes.to_string(),
+                "0".to_string(),
+                "0".to_string(),
+            ),
+            ComparisonResult::Image(r) => (
+                format!("d={}", r.hamming_distance),
+                r.verdict.clone(),
+                "0".to_string(),
+            ),
             ComparisonResult::HashOnly { identical, .. } => (
                 if *identical { "1" } else { "0" }.to_string(),
                 "0".to_string(),
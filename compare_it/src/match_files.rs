@@ -5,8 +5,9 @@
 //! - Top-K candidate selection based on fingerprint similarity
 //! - Caps to prevent combinatorial explosion
 
+use crate::bktree::SimhashIndex;
 use crate::fingerprint::simhash_similarity;
-use crate::types::{CandidatePair, CompareConfig, FileEntry, FileType, PairingStrategy};
+use crate::types::{CandidatePair, CompareConfig, CompareMode, FileEntry, FileType, PairingStrategy};
 use std::collections::HashMap;
 
 /// Generate candidate pairs for comparison
@@ -15,12 +16,22 @@ pub fn generate_candidates(
     files2: &[FileEntry],
     config: &CompareConfig,
 ) -> Vec<CandidatePair> {
+    // Image mode matches by perceptual-hash proximity, not by the text/binary
+    // fingerprint pruning used for the other modes.
+    if config.mode == CompareMode::Image {
+        return image_match(files1, files2, config.top_k, config.image_threshold);
+    }
+
     match config.pairing {
         PairingStrategy::SamePath => match_by_path(files1, files2),
         PairingStrategy::SameName => match_by_name(files1, files2),
-        PairingStrategy::AllVsAll => {
-            all_vs_all_match(files1, files2, config.top_k, config.max_pairs)
-        }
+        PairingStrategy::AllVsAll => all_vs_all_match(
+            files1,
+            files2,
+            config.top_k,
+            config.max_pairs,
+            config.simhash_radius,
+        ),
     }
 }
 
@@ -39,8 +50,7 @@ fn match_by_path(files1: &[FileEntry], files2: &[FileEntry]) -> Vec<CandidatePai
                 file1: f1.clone(),
                 file2: (*f2).clone(),
                 estimated_similarity: estimate_similarity(f1, f2),
-                exact_hash_match: !f1.content_hash.is_empty()
-                    && f1.content_hash == f2.content_hash,
+                exact_hash_match: exact_hash_match(f1, f2),
             })
         })
         .collect()
@@ -73,8 +83,7 @@ fn match_by_name(files1: &[FileEntry], files2: &[FileEntry]) -> Vec<CandidatePai
                         file1: f1.clone(),
                         file2: (*f2).clone(),
                         estimated_similarity: estimate_similarity(f1, f2),
-                        exact_hash_match: !f1.content_hash.is_empty()
-                            && f1.content_hash == f2.content_hash,
+                        exact_hash_match: exact_hash_match(f1, f2),
                     });
                 }
             }
@@ -84,12 +93,57 @@ fn match_by_name(files1: &[FileEntry], files2: &[FileEntry]) -> Vec<CandidatePai
     pairs
 }
 
+/// Match images by perceptual-hash proximity.
+///
+/// Every file1 perceptual hash is inserted into a Hamming BK-tree; each file2
+/// hash then queries the tree for neighbours within `threshold` bits and keeps
+/// the `top_k` nearest as candidate pairs. Files whose perceptual hash failed
+/// to compute (decode errors) carry no simhash and are skipped, as are
+/// non-image files, whose simhash is not a perceptual hash.
+fn image_match(
+    files1: &[FileEntry],
+    files2: &[FileEntry],
+    top_k: usize,
+    threshold: u32,
+) -> Vec<CandidatePair> {
+    let mut index = SimhashIndex::new();
+    for (i, f1) in files1.iter().enumerate() {
+        if let Some(hash) = f1.simhash.filter(|_| f1.file_type.is_image()) {
+            index.insert(hash, i);
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for f2 in files2 {
+        let Some(hash) = f2.simhash.filter(|_| f2.file_type.is_image()) else { continue };
+        let mut neighbors = index.find_similar(hash, threshold);
+        // Nearest (smallest Hamming distance) first.
+        neighbors.sort_by_key(|&(_, d)| d);
+        for (idx, d) in neighbors.into_iter().take(top_k) {
+            pairs.push(CandidatePair {
+                file1: files1[idx].clone(),
+                file2: f2.clone(),
+                estimated_similarity: 1.0 - d as f64 / 64.0,
+                exact_hash_match: false,
+            });
+        }
+    }
+
+    pairs.sort_by(|a, b| {
+        b.estimated_similarity
+            .partial_cmp(&a.estimated_similarity)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    pairs
+}
+
 /// All-vs-all matching with candidate pruning and Top-K selection
 fn all_vs_all_match(
     files1: &[FileEntry],
     files2: &[FileEntry],
     top_k: usize,
     max_pairs: Option<usize>,
+    simhash_radius: u32,
 ) -> Vec<CandidatePair> {
     let mut all_pairs = Vec::new();
 
@@ -115,7 +169,9 @@ fn all_vs_all_match(
             if let Some(matches) = hash_map2.get(f1.content_hash.as_str()) {
                 // Find first unmatched
                 for f2 in matches {
-                    if !matched_in_set2.contains(f2.path.as_path()) {
+                    if f1.hash_algorithm == f2.hash_algorithm
+                        && !matched_in_set2.contains(f2.path.as_path())
+                    {
                         all_pairs.push(CandidatePair {
                             file1: f1.clone(),
                             file2: (*f2).clone(),
@@ -142,13 +198,36 @@ fn all_vs_all_match(
         .filter(|f| !matched_in_set2.contains(f.path.as_path()))
         .collect();
 
+    // Build a BK-tree over the simhashes in set2 so similarity queries visit
+    // only the branches the triangle inequality cannot prune, instead of
+    // scanning every unmatched file. Entries without a simhash
+    // (binary/unknown) are absent; set1 files without one fall back to the
+    // linear path below.
+    let mut index = SimhashIndex::new();
+    for (i, f2) in unmatched2.iter().enumerate() {
+        if let Some(hash) = f2.simhash {
+            index.insert(hash, i);
+        }
+    }
+
     // For each file in set1, find top-k candidates in set2
     for f1 in &unmatched1 {
-        let mut candidates: Vec<(&FileEntry, f64)> = unmatched2
-            .iter()
-            .filter(|f2| passes_blocking_rules(f1, f2))
-            .map(|f2| (*f2, estimate_similarity(f1, f2)))
-            .collect();
+        let mut candidates: Vec<(&FileEntry, f64)> = match f1.simhash {
+            // Files with a simhash (text, CSV/TSV, images): query the BK-tree
+            // within the radius.
+            Some(hash) if !index.is_empty() => index
+                .find_similar(hash, simhash_radius)
+                .into_iter()
+                .map(|(idx, d)| (unmatched2[idx], 1.0 - d as f64 / 64.0))
+                .filter(|(f2, _)| passes_blocking_rules(f1, f2))
+                .collect(),
+            // No simhash (binary/unknown): fall back to the linear scan.
+            _ => unmatched2
+                .iter()
+                .filter(|f2| passes_blocking_rules(f1, f2))
+                .map(|f2| (*f2, estimate_similarity(f1, f2)))
+                .collect(),
+        };
 
         // Sort by similarity (descending)
         candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
@@ -207,7 +286,21 @@ fn passes_blocking_rules(f1: &FileEntry, f2: &FileEntry) -> bool {
 
     // Rule 4: Compatible file types
     match (&f1.file_type, &f2.file_type) {
-        (FileType::Binary, FileType::Binary) => true,
+        // Images only ever pair with other images.
+        (FileType::Image, FileType::Image) => true,
+        (FileType::Image, _) | (_, FileType::Image) => false,
+        (FileType::Binary, FileType::Binary) => {
+            // Rule 5: Two same-size binaries with known, differing prefix hashes
+            // cannot be byte-identical, so reject them without reading the whole
+            // file. Matching (or absent) prefix hashes let the pair proceed to
+            // full-hash confirmation.
+            if f1.size == f2.size {
+                if let (Some(p1), Some(p2)) = (&f1.partial_hash, &f2.partial_hash) {
+                    return p1 == p2;
+                }
+            }
+            true
+        }
         (FileType::Binary, _) | (_, FileType::Binary) => false,
         _ => true,
     }
@@ -235,10 +328,19 @@ fn extensions_compatible(ext1: &str, ext2: &str) -> bool {
         || in_same_group(ext1, ext2, &config_exts)
 }
 
+/// Whether two files share a non-empty content hash produced by the *same*
+/// algorithm. Comparing hashes from different algorithms would be meaningless,
+/// so a mismatched `hash_algorithm` never counts as an exact match.
+fn exact_hash_match(f1: &FileEntry, f2: &FileEntry) -> bool {
+    !f1.content_hash.is_empty()
+        && f1.hash_algorithm == f2.hash_algorithm
+        && f1.content_hash == f2.content_hash
+}
+
 /// Estimate similarity between two files based on fingerprints
 fn estimate_similarity(f1: &FileEntry, f2: &FileEntry) -> f64 {
     // Exact hash match
-    if !f1.content_hash.is_empty() && f1.content_hash == f2.content_hash {
+    if exact_hash_match(f1, f2) {
         return 1.0;
     }
 
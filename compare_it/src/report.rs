@@ -315,7 +315,21 @@ fn build_html_report(
                 "0".to_string(),
                 "0".to_string(),
             ),
-            ComparisonResult::Error { error, .. } => ("error", "-".to_string(), "-".to_string(), error.clone()),
+            ComparisonResult::Json(r) => (
+                "json",
+                r.matching_leaf_nodes.to_string(),
+                "0".to_string(),
+                "0".to_string(),
+            ),
+            ComparisonResult::Image(r) => (
+                "image",
+                format!("d={}", r.hamming_distance),
+                escape_html(&r.verdict),
+                "0".to_string(),
+            ),
+            ComparisonResult::Error { error, .. } => {
+                ("error", "-".to_string(), "-".to_string(), escape_html(error))
+            }
         };
 
         // Generate artifact links
@@ -324,18 +338,18 @@ fn build_html_report(
             let sanitized = sanitize_for_filename(linked_id);
             let mut links = Vec::new();
 
+            let dir_href = escape_html(&dir.display().to_string());
+            let sanitized = escape_html(&sanitized);
             if matches!(result, ComparisonResult::Text(_)) && !identical {
                 links.push(format!(
                     "<a href=\"{}/patches/{}.diff\">patch</a>",
-                    dir.display(),
-                    sanitized
+                    dir_href, sanitized
                 ));
             }
             if matches!(result, ComparisonResult::Structured(_)) && !identical {
                 links.push(format!(
                     "<a href=\"{}/mismatches/{}.json\">details</a>",
-                    dir.display(),
-                    sanitized
+                    dir_href, sanitized
                 ));
             }
             links.join(" | ")
@@ -361,10 +375,10 @@ fn build_html_report(
 "#,
             status_badge,
             status_text,
-            file1,
-            truncate_path(file1, 40),
-            file2,
-            truncate_path(file2, 40),
+            escape_html(file1),
+            escape_html(&truncate_path(file1, 40)),
+            escape_html(file2),
+            escape_html(&truncate_path(file2, 40)),
             sim_class,
             (similarity * 100.0).round(),
             similarity * 100.0,
@@ -381,6 +395,9 @@ fn build_html_report(
         </div>
 "#);
 
+    // Per-cell mismatch details with inline highlighting
+    html.push_str(&build_mismatch_details(results));
+
     // JavaScript for sorting
     html.push_str(r#"
     <script>
@@ -425,6 +442,115 @@ fn build_html_report(
     html
 }
 
+/// Build the per-cell mismatch detail section with inline highlighting.
+///
+/// Each structured result with field mismatches gets a collapsible block whose
+/// rows render the character-level diff as `<del>`/`<ins>` spans colored from
+/// the existing theme variables.
+fn build_mismatch_details(results: &[ComparisonResult]) -> String {
+    let mut html = String::new();
+
+    let has_details = results.iter().any(|r| {
+        matches!(r, ComparisonResult::Structured(s) if !s.field_mismatches.is_empty())
+    });
+    if !has_details {
+        return html;
+    }
+
+    html.push_str(r#"
+    <style>
+        .cell-diff { font-family: ui-monospace, Menlo, Consolas, monospace; }
+        .cell-diff del { background: rgba(248,81,73,.2); color: var(--danger); text-decoration: none; }
+        .cell-diff ins { background: rgba(63,185,80,.2); color: var(--success); text-decoration: none; }
+    </style>
+    <div class="container">
+"#);
+
+    for result in results {
+        if let ComparisonResult::Structured(s) = result {
+            if s.field_mismatches.is_empty() {
+                continue;
+            }
+            html.push_str(&format!(
+                "        <details><summary>{} ↔ {}</summary>\n",
+                escape_html(&s.file1_path),
+                escape_html(&s.file2_path)
+            ));
+            for column in &s.field_mismatches {
+                html.push_str(&format!(
+                    "            <h4>{} ({} mismatches)</h4>\n",
+                    escape_html(&column.column_name),
+                    column.mismatch_count
+                ));
+                for mismatch in &column.sample_mismatches {
+                    html.push_str(&format!(
+                        "            <div class=\"cell-diff\">{}: {}</div>\n",
+                        escape_html(&mismatch.key),
+                        render_cell_diff(mismatch)
+                    ));
+                }
+            }
+            html.push_str("        </details>\n");
+        }
+    }
+
+    html.push_str("    </div>\n");
+    html
+}
+
+/// Render one field mismatch's diff chunks as inline `<del>`/`<ins>` markup,
+/// falling back to a plain `old → new` arrow when no chunks were computed.
+fn render_cell_diff(mismatch: &crate::types::FieldMismatch) -> String {
+    use crate::types::DiffOp;
+
+    if mismatch.diff_chunks.is_empty() {
+        return format!(
+            "{} → {}",
+            escape_html(&mismatch.value1),
+            escape_html(&mismatch.value2)
+        );
+    }
+
+    let mut out = String::new();
+    for chunk in &mismatch.diff_chunks {
+        let text = escape_html(&chunk.text);
+        match chunk.op {
+            DiffOp::Equal => out.push_str(&text),
+            DiffOp::Delete => {
+                out.push_str("<del>");
+                out.push_str(&text);
+                out.push_str("</del>");
+            }
+            DiffOp::Insert => {
+                out.push_str("<ins>");
+                out.push_str(&text);
+                out.push_str("</ins>");
+            }
+        }
+    }
+    out
+}
+
+/// Escape a string for safe interpolation into HTML text or attribute values.
+///
+/// Maps the five characters that are significant in HTML (`&`, `<`, `>`, `"`,
+/// `'`) to their named/numeric entities so real-world filenames and compared
+/// data can't corrupt the table or inject markup.
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 /// Truncate a path string for display
 fn truncate_path(path: &str, max_len: usize) -> String {
     if path.len() <= max_len {
@@ -0,0 +1,212 @@
+//! Structural (AST-aware) source diffing.
+//!
+//! Source files are parsed with a tree-sitter grammar selected by extension and
+//! flattened into a sequence of typed leaf tokens (node kind + source text,
+//! whitespace-only nodes dropped). Diffing those token sequences instead of raw
+//! lines means reformatting or reindentation no longer shows up as a
+//! difference. The token diff is order-sensitive, so a block moved to another
+//! place still shows up as a deletion plus an insertion. When no grammar is
+//! available the caller falls back to the line-level diff.
+
+use similar::{capture_diff_slices, Algorithm, DiffTag};
+use tree_sitter::{Language, Node, Parser};
+
+/// A single leaf token from a parsed syntax tree.
+struct Token {
+    /// Node kind (e.g. `identifier`, `string_literal`)
+    kind: String,
+    /// Source text of the leaf
+    text: String,
+    /// 0-based line where the token starts
+    line: usize,
+}
+
+/// Outcome of a structural diff between two token sequences.
+pub struct StructuralDiff {
+    /// Tokens unchanged between the two files
+    pub common_tokens: usize,
+    /// Tokens present only in file 1
+    pub only_in_file1: usize,
+    /// Tokens present only in file 2
+    pub only_in_file2: usize,
+    /// 0-based file-1 line numbers touched by a change
+    pub changed_lines: Vec<usize>,
+    /// Compact token-level diff, prefixed `-`/`+` per side
+    pub detailed_diff: String,
+}
+
+impl StructuralDiff {
+    /// Similarity as `common / (common + only1 + only2)`, `1.0` when both empty.
+    pub fn similarity(&self) -> f64 {
+        let total = self.common_tokens + self.only_in_file1 + self.only_in_file2;
+        if total > 0 {
+            self.common_tokens as f64 / total as f64
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Return the tree-sitter grammar for a file extension, if one is bundled.
+fn language_for(ext: &str) -> Option<Language> {
+    match ext {
+        "rs" => Some(tree_sitter_rust::language()),
+        "py" => Some(tree_sitter_python::language()),
+        "json" => Some(tree_sitter_json::language()),
+        _ => None,
+    }
+}
+
+/// Parse `source` with the grammar for `ext` and flatten it into leaf tokens,
+/// dropping whitespace-only leaves. Returns `None` when no grammar is available
+/// or the parser cannot be constructed.
+fn tokenize(ext: &str, source: &str) -> Option<Vec<Token>> {
+    let language = language_for(ext)?;
+    let mut parser = Parser::new();
+    parser.set_language(language).ok()?;
+    let tree = parser.parse(source, None)?;
+
+    let mut tokens = Vec::new();
+    collect_leaves(tree.root_node(), source.as_bytes(), &mut tokens);
+    Some(tokens)
+}
+
+/// Depth-first walk collecting leaf nodes (those without children).
+fn collect_leaves(node: Node, src: &[u8], out: &mut Vec<Token>) {
+    if node.child_count() == 0 {
+        let text = node.utf8_text(src).unwrap_or("");
+        if !text.trim().is_empty() {
+            out.push(Token {
+                kind: node.kind().to_string(),
+                text: text.to_string(),
+                line: node.start_position().row,
+            });
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_leaves(child, src, out);
+    }
+}
+
+/// Compute a structural diff of two sources, or `None` when either extension
+/// has no grammar (the caller then falls back to the line-level diff).
+pub fn structural_diff(
+    ext1: &str,
+    src1: &str,
+    ext2: &str,
+    src2: &str,
+    max_diff_bytes: usize,
+) -> Option<StructuralDiff> {
+    let tokens1 = tokenize(ext1, src1)?;
+    let tokens2 = tokenize(ext2, src2)?;
+
+    // Compare on (kind, text) so a reformatted but semantically identical token
+    // still matches; line numbers are carried along for reporting.
+    let keys1: Vec<(&str, &str)> = tokens1
+        .iter()
+        .map(|t| (t.kind.as_str(), t.text.as_str()))
+        .collect();
+    let keys2: Vec<(&str, &str)> = tokens2
+        .iter()
+        .map(|t| (t.kind.as_str(), t.text.as_str()))
+        .collect();
+
+    let mut common_tokens = 0;
+    let mut only_in_file1 = 0;
+    let mut only_in_file2 = 0;
+    let mut changed_lines = Vec::new();
+    let mut detailed_diff = String::new();
+
+    for op in capture_diff_slices(Algorithm::Myers, &keys1, &keys2) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        match tag {
+            DiffTag::Equal => common_tokens += old_range.len(),
+            DiffTag::Delete => {
+                only_in_file1 += old_range.len();
+                append_side(&mut detailed_diff, '-', &tokens1[old_range.clone()], max_diff_bytes);
+                record_lines(&mut changed_lines, &tokens1[old_range]);
+            }
+            DiffTag::Insert => {
+                only_in_file2 += new_range.len();
+                append_side(&mut detailed_diff, '+', &tokens2[new_range], max_diff_bytes);
+            }
+            DiffTag::Replace => {
+                only_in_file1 += old_range.len();
+                only_in_file2 += new_range.len();
+                append_side(
+                    &mut detailed_diff,
+                    '-',
+                    &tokens1[old_range.clone()],
+                    max_diff_bytes,
+                );
+                append_side(&mut detailed_diff, '+', &tokens2[new_range], max_diff_bytes);
+                record_lines(&mut changed_lines, &tokens1[old_range]);
+            }
+        }
+    }
+
+    changed_lines.sort_unstable();
+    changed_lines.dedup();
+
+    Some(StructuralDiff {
+        common_tokens,
+        only_in_file1,
+        only_in_file2,
+        changed_lines,
+        detailed_diff,
+    })
+}
+
+/// Append a run of tokens to the detailed diff on one side, respecting the byte
+/// budget so a huge change can't blow up memory.
+fn append_side(out: &mut String, prefix: char, tokens: &[Token], max_diff_bytes: usize) {
+    use std::fmt::Write;
+    for token in tokens {
+        if out.len() >= max_diff_bytes {
+            break;
+        }
+        let _ = writeln!(out, "{}{} {}", prefix, token.kind, token.text);
+    }
+}
+
+/// Record the (sorted, deduped per call) file-1 line numbers of changed tokens.
+fn record_lines(lines: &mut Vec<usize>, tokens: &[Token]) {
+    for token in tokens {
+        if lines.last() != Some(&token.line) {
+            lines.push(token.line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reformatting_is_not_a_difference() {
+        // Same tokens, different whitespace/indentation → fully similar.
+        let a = "fn main() { let x = 1; }";
+        let b = "fn main() {\n    let x = 1;\n}\n";
+        let diff = structural_diff("rs", a, "rs", b, 1 << 20).expect("grammar available");
+        assert_eq!(diff.only_in_file1, 0);
+        assert_eq!(diff.only_in_file2, 0);
+        assert_eq!(diff.similarity(), 1.0);
+    }
+
+    #[test]
+    fn test_semantic_change_is_reported() {
+        let a = "fn main() { let x = 1; }";
+        let b = "fn main() { let x = 2; }";
+        let diff = structural_diff("rs", a, "rs", b, 1 << 20).expect("grammar available");
+        assert!(diff.only_in_file1 + diff.only_in_file2 > 0);
+        assert!(!diff.changed_lines.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_extension_falls_back() {
+        assert!(structural_diff("txt", "a", "txt", "b", 1 << 20).is_none());
+    }
+}
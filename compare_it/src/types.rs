@@ -3,6 +3,7 @@
 //! This module defines all the shared types used across the comparison pipeline.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// File type detected during indexing
@@ -16,6 +17,8 @@ pub enum FileType {
     Tsv,
     /// Binary file (hash-only comparison)
     Binary,
+    /// Image file (perceptual-hash comparison)
+    Image,
     /// Unknown/unreadable
     Unknown,
 }
@@ -28,6 +31,29 @@ impl FileType {
     pub fn is_text(&self) -> bool {
         matches!(self, FileType::Text)
     }
+
+    pub fn is_image(&self) -> bool {
+        matches!(self, FileType::Image)
+    }
+}
+
+/// Algorithm used to produce a file's content hash.
+///
+/// Blake3 is the integrity-sensitive default; CRC32 and XXH3 are far faster
+/// and fine for pure dedup workloads where adversarial collision resistance is
+/// irrelevant. The algorithm is recorded on each [`FileEntry`] so hashes from
+/// mixed-algorithm indexes are never compared as if interchangeable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+pub enum HashAlgorithm {
+    /// Cryptographic hash (default, integrity-sensitive)
+    #[default]
+    Blake3,
+    /// CRC32 checksum (fastest, weakest)
+    Crc32,
+    /// XXH3 64-bit non-cryptographic hash (fast)
+    Xxh3,
+    /// SHA-256 (widely recognized cryptographic checksum)
+    Sha256,
 }
 
 /// Represents a single indexed file with metadata and fingerprints
@@ -37,12 +63,20 @@ pub struct FileEntry {
     pub path: PathBuf,
     /// File size in bytes
     pub size: u64,
+    /// Last-modified time as seconds since the Unix epoch, when available
+    pub modified_time: Option<u64>,
     /// Detected file type
     pub file_type: FileType,
     /// File extension (lowercase, without dot)
     pub extension: String,
-    /// Blake3 hash of file contents (hex string)
+    /// Content hash of the file (hex string)
     pub content_hash: String,
+    /// Algorithm that produced `content_hash`
+    pub hash_algorithm: HashAlgorithm,
+    /// Hash of the first block only, used as a cheap pre-filter before the
+    /// full content hash is computed (set during fingerprinting for every
+    /// file, whether or not its size collides with another file's)
+    pub partial_hash: Option<String>,
     /// Simhash fingerprint for similarity matching (text files)
     pub simhash: Option<u64>,
     /// Schema signature for structured files (sorted column names hash)
@@ -54,7 +88,7 @@ pub struct FileEntry {
 }
 
 /// Comparison mode selection
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
 pub enum CompareMode {
     /// Auto-detect based on file type
     #[default]
@@ -63,16 +97,53 @@ pub enum CompareMode {
     Text,
     /// Force structured (CSV/TSV) comparison
     Structured,
+    /// Force semantic JSON comparison
+    Json,
+    /// Force perceptual image comparison
+    Image,
+    /// Delegate every pair to the configured external comparator
+    External,
 }
 
 /// Similarity scoring algorithm
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
 pub enum SimilarityAlgorithm {
     /// Diff-based: common / (common + only_in_1 + only_in_2)
     #[default]
     Diff,
     /// Character-level Jaro-Winkler similarity
     CharJaro,
+    /// AST-aware diff over typed syntax tokens (falls back to `Diff` when no
+    /// tree-sitter grammar is available for the file extension)
+    Structural,
+}
+
+/// Source text encoding for the CSV/TSV parser.
+///
+/// For every variant a leading UTF-8 BOM is stripped and a UTF-16 BOM selects
+/// UTF-16 decoding. Without a UTF-16 BOM, `Auto` decodes as lossy UTF-8, which
+/// keeps well-formed UTF-8 exact while tolerating stray legacy bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum FileEncoding {
+    /// Detect from a BOM, defaulting to UTF-8 (lossy) when none is present.
+    #[default]
+    Auto,
+    /// Force UTF-8 (lossy on invalid sequences).
+    Utf8,
+    /// Transcode from Latin-1 (ISO-8859-1).
+    Latin1,
+    /// Transcode from Windows-1252.
+    Windows1252,
+}
+
+/// Strategy for deciding whether two files are byte-identical
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum HashStrategy {
+    /// Always compare full content hashes
+    #[default]
+    Full,
+    /// Compare a cheap prefix hash first, full hash only on a prefix+size match
+    TwoStage,
 }
 
 /// Pairing strategy for folder comparison
@@ -87,6 +158,46 @@ pub enum PairingStrategy {
     AllVsAll,
 }
 
+/// Per-column matching rule for structured comparison.
+///
+/// Modeled on contract-testing matchers: instead of a single numeric tolerance,
+/// each column can declare how its values are considered equal (by type, by a
+/// regex, as a parsed timestamp, etc.). This lets exports that format the same
+/// data differently still compare as identical.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MatchingRule {
+    /// Exact string match, with numeric tolerance as a fallback (the default).
+    Equality,
+    /// Case-insensitive string match.
+    IgnoreCase,
+    /// Values match if both parse as the same kind (int/float/bool/string).
+    Type,
+    /// Both values must parse as numbers (within numeric tolerance).
+    Number,
+    /// Both values must parse as integers and be equal.
+    Integer,
+    /// Both values must parse as decimals (within numeric tolerance).
+    Decimal,
+    /// Both values must match the given regular expression.
+    Regex(String),
+    /// Both values are parsed with the chrono datetime format and compared.
+    Timestamp(String),
+    /// Both values are parsed with the chrono date format and compared.
+    Date(String),
+    /// Both values must contain the given substring.
+    Include(String),
+    /// Both values must be at least this many characters long.
+    MinLength(usize),
+    /// Both values must be at most this many characters long.
+    MaxLength(usize),
+}
+
+impl Default for MatchingRule {
+    fn default() -> Self {
+        MatchingRule::Equality
+    }
+}
+
 /// Text normalization options
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NormalizationOptions {
@@ -111,41 +222,125 @@ pub struct CompareConfig {
     pub pairing: PairingStrategy,
     /// Top-K candidates per file in all-vs-all mode
     pub top_k: usize,
+    /// Hamming radius for the simhash BK-tree nearest-neighbor search
+    pub simhash_radius: u32,
     /// Maximum number of pairs to compare
     pub max_pairs: Option<usize>,
     /// Key columns for structured comparison
     pub key_columns: Vec<String>,
     /// Numeric tolerance for structured comparison
     pub numeric_tolerance: f64,
+    /// Per-column matching rules for structured comparison
+    pub matching_rules: HashMap<String, MatchingRule>,
+    /// Fallback matching rule for columns without an explicit rule
+    pub default_matching_rule: MatchingRule,
+    /// Levenshtein radius for fuzzy key linkage (None disables the pass)
+    pub fuzzy_key_radius: Option<u32>,
+    /// Source encoding for the structured (CSV/TSV) parser
+    pub encoding: FileEncoding,
+    /// Max Hamming distance for image near-duplicate candidate matching
+    pub image_threshold: u32,
+    /// Match JSON array elements order-insensitively (by best similarity)
+    pub json_array_order_insensitive: bool,
     /// Text normalization options
     pub normalization: NormalizationOptions,
     /// Similarity algorithm
     pub similarity_algorithm: SimilarityAlgorithm,
     /// Maximum bytes for detailed diff output
     pub max_diff_bytes: usize,
+    /// Highlight intra-line changes at the character level in unified diffs
+    pub inline_char_diff: bool,
+    /// Algorithm used for content hashing
+    pub hash_algorithm: HashAlgorithm,
+    /// Strategy for binary identity checks
+    pub hash_strategy: HashStrategy,
+    /// Optional external comparator for unsupported file types
+    pub external: Option<crate::external::ExternalComparator>,
+    /// Layout for the primary JSON output file
+    pub json_format: crate::export::JsonFormat,
     /// Output paths for exports
     pub output_jsonl: Option<PathBuf>,
     pub output_csv: Option<PathBuf>,
+    pub output_html: Option<PathBuf>,
     pub output_dir: Option<PathBuf>,
+    /// Combined `{ summary, results }` JSON report for CI pipelines
+    pub output_json: Option<PathBuf>,
+    /// Drop pretty-printing from the combined JSON report
+    pub output_json_compact: bool,
+    /// Exit with a nonzero status when any pair differs or errors
+    pub fail_on_diff: bool,
+    /// Path to the persistent fingerprint cache, if enabled
+    pub cache_path: Option<PathBuf>,
+    /// Directory traversal options for indexing
+    pub walk: crate::index::WalkOptions,
+    /// Pattern-scoped rules loaded from a YAML config, tried in order and
+    /// merged over the global defaults for each pair (empty when none)
+    pub rules: Vec<Rule>,
     /// Verbose output
     pub verbose: bool,
 }
 
+/// A pattern-scoped override loaded from a YAML config file.
+///
+/// Every field but `pattern` is optional; a present field replaces the
+/// corresponding global [`CompareConfig`] default for pairs whose first file
+/// path matches `pattern`. Rules are tried in declaration order, first match
+/// wins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    /// Glob tested against `file1.path` (e.g. `**/*.csv`, `reports/*.tsv`)
+    pub pattern: String,
+    /// Override the comparison mode
+    #[serde(default)]
+    pub mode: Option<CompareMode>,
+    /// Override the structured key columns
+    #[serde(default)]
+    pub key_columns: Option<Vec<String>>,
+    /// Override the numeric tolerance
+    #[serde(default)]
+    pub numeric_tolerance: Option<f64>,
+    /// Override the text normalization options
+    #[serde(default)]
+    pub normalization: Option<NormalizationOptions>,
+    /// Override the similarity algorithm
+    #[serde(default)]
+    pub similarity_algorithm: Option<SimilarityAlgorithm>,
+}
+
 impl Default for CompareConfig {
     fn default() -> Self {
         Self {
             mode: CompareMode::Auto,
             pairing: PairingStrategy::AllVsAll,
             top_k: 3,
+            simhash_radius: 12,
             max_pairs: None,
             key_columns: Vec::new(),
             numeric_tolerance: 0.0001,
+            matching_rules: HashMap::new(),
+            default_matching_rule: MatchingRule::Equality,
+            fuzzy_key_radius: None,
+            encoding: FileEncoding::Auto,
+            image_threshold: 10,
+            json_array_order_insensitive: false,
             normalization: NormalizationOptions::default(),
             similarity_algorithm: SimilarityAlgorithm::Diff,
             max_diff_bytes: 1024 * 1024, // 1MB default
+            inline_char_diff: false,
+            hash_algorithm: HashAlgorithm::Blake3,
+            hash_strategy: HashStrategy::Full,
+            external: None,
+            json_format: crate::export::JsonFormat::Jsonl,
             output_jsonl: None,
             output_csv: None,
+            output_html: None,
             output_dir: None,
+            output_json: None,
+            output_json_compact: false,
+            fail_on_diff: false,
+            cache_path: None,
+            walk: crate::index::WalkOptions::default(),
+            rules: Vec::new(),
             verbose: false,
         }
     }
@@ -186,12 +381,52 @@ pub struct ColumnMismatch {
     pub sample_mismatches: Vec<FieldMismatch>,
 }
 
+/// Edit operation for a character/word-level cell diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiffOp {
+    /// Text common to both values
+    Equal,
+    /// Text present only in value 1
+    Delete,
+    /// Text present only in value 2
+    Insert,
+}
+
+/// A contiguous run of text tagged with its edit operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffChunk {
+    pub op: DiffOp,
+    pub text: String,
+}
+
 /// A single field mismatch sample
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldMismatch {
     pub key: String,
     pub value1: String,
     pub value2: String,
+    /// Character-level diff chunks between `value1` and `value2`
+    #[serde(default)]
+    pub diff_chunks: Vec<DiffChunk>,
+}
+
+/// Two rows linked by a near-matching (not exact) composite key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzyMatch {
+    /// Composite key from file 1
+    pub key1: String,
+    /// Nearest composite key from file 2
+    pub key2: String,
+    /// Levenshtein distance between the two keys
+    pub key_distance: u32,
+    /// Field-level mismatches between the two linked rows
+    pub field_mismatches: Vec<FieldMismatch>,
+}
+
+/// Default schema similarity for results loaded from older reports that predate
+/// the field: assume identical schemas.
+fn default_schema_similarity() -> f64 {
+    1.0
 }
 
 /// Result of comparing two structured files (CSV/TSV mode)
@@ -219,16 +454,91 @@ pub struct StructuredComparisonResult {
     pub columns_only_in_file1: Vec<String>,
     pub columns_only_in_file2: Vec<String>,
     pub common_columns: Vec<String>,
+    /// Jaccard overlap of the two header sets (1.0 = identical schemas).
+    ///
+    /// Surfaces "same table, extra column" relationships even when the record
+    /// comparison finds the rows unrelated.
+    #[serde(default = "default_schema_similarity")]
+    pub schema_similarity: f64,
+    /// Containment of file 1's columns within file 2's (`|A∩B| / |A|`); `1.0`
+    /// means file 1's schema is a subset of file 2's (file 2 has extra columns).
+    #[serde(default = "default_schema_similarity")]
+    pub schema_containment_file1: f64,
+    /// Containment of file 2's columns within file 1's; the mirror of
+    /// [`schema_containment_file1`](Self::schema_containment_file1).
+    #[serde(default = "default_schema_similarity")]
+    pub schema_containment_file2: f64,
+    /// Rows linked by a near-matching key (empty unless fuzzy linkage is on)
+    #[serde(default)]
+    pub fuzzy_matches: Vec<FuzzyMatch>,
     /// Whether files are identical
     pub identical: bool,
 }
 
+/// Kind of structural difference found during JSON comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum JsonDiffKind {
+    /// Path present only in file 1
+    KeyOnlyInFile1,
+    /// Path present only in file 2
+    KeyOnlyInFile2,
+    /// Scalar values differ at a shared path
+    ValueMismatch,
+}
+
+/// A single structural difference at a JSON-pointer path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonDifference {
+    /// JSON-pointer-style path (e.g. `/items/3/name`)
+    pub path: String,
+    pub kind: JsonDiffKind,
+    pub value1: Option<String>,
+    pub value2: Option<String>,
+}
+
+/// Result of comparing two JSON files semantically
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonComparisonResult {
+    /// Stable linked ID
+    pub linked_id: String,
+    pub file1_path: String,
+    pub file2_path: String,
+    /// Leaf-node counts driving the similarity score
+    pub total_leaf_nodes: usize,
+    pub matching_leaf_nodes: usize,
+    /// Structural differences, in document walk order (object keys sorted)
+    pub differences: Vec<JsonDifference>,
+    pub similarity_score: f64,
+    pub identical: bool,
+}
+
+/// Result of comparing two images by perceptual hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageComparisonResult {
+    /// Stable linked ID
+    pub linked_id: String,
+    pub file1_path: String,
+    pub file2_path: String,
+    /// Hamming distance between the two 64-bit perceptual hashes
+    pub hamming_distance: u32,
+    /// Banded verdict (`near-identical`, `similar`, or `different`)
+    pub verdict: String,
+    /// Similarity score (0.0 to 1.0)
+    pub similarity_score: f64,
+    /// Whether the images are considered the same
+    pub identical: bool,
+}
+
 /// Unified comparison result (either text or structured)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ComparisonResult {
     Text(TextComparisonResult),
     Structured(StructuredComparisonResult),
+    /// Semantic JSON comparison
+    Json(JsonComparisonResult),
+    /// Perceptual-hash image comparison
+    Image(ImageComparisonResult),
     /// Hash-only comparison for binary files
     HashOnly {
         linked_id: String,
@@ -251,6 +561,8 @@ impl ComparisonResult {
         match self {
             ComparisonResult::Text(r) => &r.linked_id,
             ComparisonResult::Structured(r) => &r.linked_id,
+            ComparisonResult::Json(r) => &r.linked_id,
+            ComparisonResult::Image(r) => &r.linked_id,
             ComparisonResult::HashOnly { linked_id, .. } => linked_id,
             ComparisonResult::Error { file1_path, .. } => file1_path,
         }
@@ -260,6 +572,8 @@ impl ComparisonResult {
         match self {
             ComparisonResult::Text(r) => r.similarity_score,
             ComparisonResult::Structured(r) => r.similarity_score,
+            ComparisonResult::Json(r) => r.similarity_score,
+            ComparisonResult::Image(r) => r.similarity_score,
             ComparisonResult::HashOnly { identical, .. } => {
                 if *identical {
                     1.0
@@ -275,6 +589,8 @@ impl ComparisonResult {
         match self {
             ComparisonResult::Text(r) => r.identical,
             ComparisonResult::Structured(r) => r.identical,
+            ComparisonResult::Json(r) => r.identical,
+            ComparisonResult::Image(r) => r.identical,
             ComparisonResult::HashOnly { identical, .. } => *identical,
             ComparisonResult::Error { .. } => false,
         }
@@ -284,6 +600,8 @@ impl ComparisonResult {
         match self {
             ComparisonResult::Text(r) => (&r.file1_path, &r.file2_path),
             ComparisonResult::Structured(r) => (&r.file1_path, &r.file2_path),
+            ComparisonResult::Json(r) => (&r.file1_path, &r.file2_path),
+            ComparisonResult::Image(r) => (&r.file1_path, &r.file2_path),
             ComparisonResult::HashOnly {
                 file1_path,
                 file2_path,